@@ -0,0 +1,46 @@
+// cargo bench --bench day15 [-- unfold...]
+// compares the day 15 search modes on input/2021/day15.txt, or the example
+// when there is no input, unfolded 1, 5 and 50 times by default
+
+use std::{env, fs, time::Instant};
+
+use advent_of_code_2021::{
+    day15::{Day15, Mode},
+    puzzle::Puzzle,
+};
+
+// the generic search keeps every position in a HashMap, it gets too slow past that
+const GENERIC_MAX_CELLS: usize = 1_000_000;
+
+fn main() {
+    let input = fs::read_to_string("input/2021/day15.txt").unwrap_or_else(|_| Day15::EXAMPLE.to_string());
+    let input = Day15::parse(input.trim_end()).expect("a valid day 15 input");
+
+    let sizes = env::args()
+        .skip(1)
+        .filter(|arg| arg != "--bench")
+        .map(|arg| arg.parse().expect("unfold sizes must be numbers"))
+        .collect::<Vec<usize>>();
+
+    let sizes = if sizes.is_empty() { vec![1, 5, 50] } else { sizes };
+
+    for times in sizes {
+        let start = Instant::now();
+        let cave = input.unfold(times);
+
+        println!("unfold({}): {} cells in {:?}", times, cave.cells(), start.elapsed());
+
+        for mode in [Mode::Generic, Mode::BucketQueue, Mode::AStar] {
+            if mode == Mode::Generic && cave.cells() > GENERIC_MAX_CELLS {
+                println!("  {:?}: skipped", mode);
+
+                continue;
+            }
+
+            let start = Instant::now();
+            let path = cave.safest_path_with(mode);
+
+            println!("  {:?}: risk {} in {:?}", mode, path.cost, start.elapsed());
+        }
+    }
+}
@@ -1,9 +1,10 @@
 
+use crate::{parse::{self, ParseError}, puzzle::Puzzle, solve::Answer};
+
 #[aoc_generator(day1)]
-pub fn input_generator(input: &str) -> Vec<u32> {
-    input
-        .lines()
-        .map(|l| l.parse().unwrap())
+pub fn input_generator(input: &str) -> Result<Vec<u32>, ParseError> {
+    parse::lines(input)
+        .map(|line| parse::parse(1, line, line.1, "a depth measurement"))
         .collect()
 }
 
@@ -24,6 +25,39 @@ pub fn part2(measurements: &[u32]) -> u32 {
     )
 }
 
+pub struct Day1;
+
+impl Puzzle for Day1 {
+    const DAY: u8 = 1;
+    const EXAMPLE: &'static str = "199
+200
+208
+210
+200
+207
+240
+269
+260
+263";
+    const EXAMPLE_ANSWERS: [Option<Answer>; 2] = [Some(Answer::Number(7)), Some(Answer::Number(5))];
+
+    type Input = Vec<u32>;
+    type Output1 = u32;
+    type Output2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -33,6 +67,13 @@ mod tests {
         assert_eq!(part1(&[199, 200, 208, 210, 200, 207, 240, 269, 260, 263]), 7);
     }
 
+    #[test]
+    fn input_errors() {
+        assert_eq!(input_generator("199\n200\n208"), Ok(vec![199, 200, 208]));
+        assert_eq!(input_generator("199\n\n208"), Err(ParseError::new(1, 2, 1, "a depth measurement")));
+        assert_eq!(input_generator("199\r\n20O"), Err(ParseError::new(1, 2, 1, "a depth measurement")));
+    }
+
     #[test]
     fn part1_samples() {
         assert_eq!(part1(&[1]), 0);
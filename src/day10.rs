@@ -1,8 +1,9 @@
-use core::panic;
 use std::{str::FromStr, fmt::{Display, Write}};
 
+use crate::{parse::{self, ParseError}, puzzle::Puzzle, solve::Answer};
+
 #[derive(Debug, PartialEq)]
-struct Input {
+pub struct Input {
     lines: Vec<Line>,
 }
 
@@ -35,7 +36,7 @@ impl Error {
 }
 
 impl Input {
-    pub fn parsed_lines(&self) -> Vec<Result<usize, Error>> {
+    fn parsed_lines(&self) -> Vec<Result<usize, Error>> {
         self.lines.iter().map(|line| line.parse(0)).collect::<Vec<_>>()
     }
 }
@@ -95,9 +96,11 @@ impl ChunkChar {
     }
 }
 
-impl From<char> for ChunkChar {
-    fn from(c: char) -> Self {
-        match c {
+impl TryFrom<char> for ChunkChar {
+    type Error = char;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        Ok(match c {
             '(' => ChunkChar::Left(ChunkCharType::Parenthesis),
             '[' => ChunkChar::Left(ChunkCharType::SquareBracket),
             '{' => ChunkChar::Left(ChunkCharType::CurlyBracket),
@@ -106,8 +109,8 @@ impl From<char> for ChunkChar {
             ']' => ChunkChar::Right(ChunkCharType::SquareBracket),
             '}' => ChunkChar::Right(ChunkCharType::CurlyBracket),
             '>' => ChunkChar::Right(ChunkCharType::AngleBracket),
-            _ => panic!(),
-        }
+            c => return Err(c),
+        })
     }
 }
 
@@ -132,18 +135,25 @@ struct Line {
 }
 
 impl FromStr for Line {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Line {
-            chars: s.chars()
-                .map(|c| c.into())
-                .collect::<Vec<_>>(),
-        })
+        Line::parse_line((1, s))
     }
 }
 
 impl Line {
+    fn parse_line((n, line): (usize, &str)) -> Result<Self, ParseError> {
+        Ok(Line {
+            chars: line.chars()
+                .enumerate()
+                .map(|(column, c)| ChunkChar::try_from(c)
+                    .map_err(|_| ParseError::new(10, n, column + 1, "one of ()[]{}<>"))
+                )
+                .collect::<Result<Vec<_>, _>>()?,
+        })
+    }
+
     pub fn parse(&self, position: usize) -> Result<usize, Error> {
         let mut l = 0;
 
@@ -185,16 +195,12 @@ impl Line {
 
 #[allow(unused_variables)]
 #[aoc_generator(day10)]
-fn input_generator(input: &str) -> Input {
-    Input {
-        lines: input.lines()
-            .map(|line| Line {
-                chars: line.chars()
-                    .map(|char| char.into())
-                    .collect::<Vec<_>>()
-            })
-            .collect::<Vec<_>>(),
-    }
+fn input_generator(input: &str) -> Result<Input, ParseError> {
+    Ok(Input {
+        lines: parse::lines(input)
+            .map(Line::parse_line)
+            .collect::<Result<Vec<_>, _>>()?,
+    })
 }
 
 type Output = usize;
@@ -227,13 +233,11 @@ fn part2(input: &Input) -> Output {
     scores[scores.len() / 2]
 }
 
-#[allow(unreachable_code)]
-#[cfg(test)]
-mod tests {
-    use super::*;
+pub struct Day10;
 
-    fn exemple_raw_input() -> &'static str {
-        "[({(<(())[]>[[{[]{<()<>>
+impl Puzzle for Day10 {
+    const DAY: u8 = 10;
+    const EXAMPLE: &'static str = "[({(<(())[]>[[{[]{<()<>>
 [(()[<>])]({[<{<<[]>>(
 {([(<{}[<>[]}>{[]{[(<()>
 (((({<>}<{<{<>}{[]{[]{}
@@ -242,17 +246,39 @@ mod tests {
 {<[[]]>}<{[{[{[]{()[[[]
 [<(<(<(<{}))><([]([]()
 <{([([[(<>()){}]>(<<{{
-<{([{{}}[<[[[<>{}]]]>[]]"
+<{([{{}}[<[[[<>{}]]]>[]]";
+    const EXAMPLE_ANSWERS: [Option<Answer>; 2] = [Some(Answer::Number(26397)), Some(Answer::Number(288957))];
+
+    type Input = Input;
+    type Output1 = Output;
+    type Output2 = Output;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
     }
 
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
+#[allow(unreachable_code)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     #[test]
     fn part2_provided_exemple() {
-        assert_eq!(part2(&input_generator(exemple_raw_input())), 288957);
+        assert_eq!(part2(&input_generator(Day10::EXAMPLE).unwrap()), 288957);
     }
 
     #[test]
     fn part1_provided_exemple() {
-        assert_eq!(part1(&input_generator(exemple_raw_input())), 26397);
+        assert_eq!(part1(&input_generator(Day10::EXAMPLE).unwrap()), 26397);
     }
 
     #[test]
@@ -297,7 +323,7 @@ mod tests {
 
     #[test]
     fn part1_provided_exemple_input() {
-        assert_eq!(input_generator(exemple_raw_input()), Input {
+        assert_eq!(input_generator(Day10::EXAMPLE).unwrap(), Input {
             lines: vec![
                 Line::from_str("[({(<(())[]>[[{[]{<()<>>").unwrap(),
                 Line::from_str("[(()[<>])]({[<{<<[]>>(").unwrap(),
@@ -312,4 +338,10 @@ mod tests {
             ],
         });
     }
+
+    #[test]
+    fn input_errors() {
+        assert_eq!(Line::from_str("(]x"), Err(ParseError::new(10, 1, 3, "one of ()[]{}<>")));
+        assert_eq!(input_generator("()\n([ ])"), Err(ParseError::new(10, 2, 3, "one of ()[]{}<>")));
+    }
 }
@@ -1,86 +1,51 @@
-use std::{collections::{HashMap, HashSet}, fmt::Display};
-use std::cmp::min;
+use std::{collections::HashSet, fmt::Display};
+
+use crate::{grid::{Grid, Pos}, parse::ParseError, puzzle::Puzzle, solve::Answer};
 
 #[derive(PartialEq, Clone)]
-struct Input {
-    octopuses: HashMap<Coord, usize>,
+pub struct Input {
+    octopuses: Grid<usize>,
 }
 
 impl Display for Input {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str(&(0..10).map(|x|
-            (0..10)
-                .map(|y| self.octopuses.get(&Coord(x, y)).unwrap().to_string())
-                .collect::<Vec<_>>()
-                .join("")
-        ).collect::<Vec<_>>().join("\n"))
+        Display::fmt(&self.octopuses, f)
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
-struct Coord(usize, usize);
-
 #[allow(unused_variables)]
 #[aoc_generator(day11)]
-fn input_generator(input: &str) -> Input {
-    Input {
-        octopuses: input.lines()
-            .flat_map(|line| line.chars().map(|c| c.to_digit(10).unwrap()))
-            .map(|n| usize::try_from(n).unwrap())
-            .enumerate()
-            .map(|(n, power_level)| (Coord::from(n), power_level))
-            .collect::<HashMap<_, _>>(),
-    }
+fn input_generator(input: &str) -> Result<Input, ParseError> {
+    Ok(Input {
+        octopuses: Grid::digits(11, input)?.map(|&n| usize::try_from(n).unwrap()),
+    })
 }
 
 type Output = usize;
 
-impl Coord {
-    pub fn from(n: usize) -> Self {
-        Coord(n / 10, n % 10)
-    }
-
-    pub fn neighbors(&self) -> Vec<Coord> {
-        let mut neighbors = vec![];
-
-        let xmin = if self.0 == 0 { 0 } else { self.0 - 1};
-        let ymin = if self.1 == 0 { 0 } else { self.1 - 1};
-
-        for x in xmin..=min(9, self.0 + 1) {
-            for y in ymin..=min(9, self.1 + 1) {
-                if x != self.0 || y != self.1 {
-                    neighbors.push(Coord(x, y));
-                }
-            }
-        }
-
-        neighbors
-    }
-}
-
 impl Input {
     pub fn step(&mut self) -> usize {
-        let mut flashes: HashSet<Coord> = HashSet::new();
+        let mut flashes: HashSet<Pos> = HashSet::new();
 
-        for n in 0..100 {
-            self.inc(Coord::from(n), &mut flashes);
+        for pos in self.octopuses.positions() {
+            self.inc(pos, &mut flashes);
         }
 
         flashes.len()
     }
 
-    pub fn inc(&mut self, pos: Coord, flashes: &mut HashSet<Coord>) {
+    pub fn inc(&mut self, pos: Pos, flashes: &mut HashSet<Pos>) {
         if flashes.contains(&pos) {
             return;
         }
 
-        self.octopuses.entry(pos).and_modify(|power_level| {
-            *power_level += 1;
-            *power_level %= 10;
-        });
+        let power_level = &mut self.octopuses[pos];
+
+        *power_level += 1;
+        *power_level %= 10;
 
-        if *self.octopuses.get(&pos).unwrap() == 0 && flashes.insert(pos) {
-            for p in pos.neighbors() {
+        if *power_level == 0 && flashes.insert(pos) {
+            for p in self.octopuses.neighbors8(pos).collect::<Vec<_>>() {
                 self.inc(p, flashes)
             }
         }
@@ -108,20 +73,18 @@ fn part2(input: &Input) -> Output {
     let mut i = input.clone();
     let mut step = 1;
 
-    while i.step() != 100 {
+    while i.step() != i.octopuses.len() {
         step += 1;
     }
 
     step
 }
 
-#[allow(unreachable_code)]
-#[cfg(test)]
-mod tests {
-    use super::*;
+pub struct Day11;
 
-    fn exemple_raw_input() -> &'static str {
-        "5483143223
+impl Puzzle for Day11 {
+    const DAY: u8 = 11;
+    const EXAMPLE: &'static str = "5483143223
 2745854711
 5264556173
 6141336146
@@ -130,45 +93,49 @@ mod tests {
 2176841721
 6882881134
 4846848554
-5283751526"
+5283751526";
+    const EXAMPLE_ANSWERS: [Option<Answer>; 2] = [Some(Answer::Number(1656)), Some(Answer::Number(195))];
+
+    type Input = Input;
+    type Output1 = Output;
+    type Output2 = Output;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
     }
 
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
+#[allow(unreachable_code)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     #[test]
     fn part2_provided_exemple() {
-        assert_eq!(part2(&input_generator(exemple_raw_input())), 195);
+        assert_eq!(part2(&input_generator(Day11::EXAMPLE).unwrap()), 195);
     }
 
     #[test]
     fn part1_provided_exemple() {
-        assert_eq!(part1(&input_generator(exemple_raw_input())), 1656);
+        assert_eq!(part1(&input_generator(Day11::EXAMPLE).unwrap()), 1656);
     }
 
     #[test]
     fn input_print() {
-        assert_eq!(format!("{}", input_generator(exemple_raw_input())), exemple_raw_input());
+        assert_eq!(format!("{}", input_generator(Day11::EXAMPLE).unwrap()), Day11::EXAMPLE);
     }
 
     #[test]
-    fn coord_neigbors() {
-        assert_eq!(Coord(1, 1).neighbors(), vec![
-            Coord(0, 0),
-            Coord(0, 1),
-            Coord(0, 2),
-            Coord(1, 0),
-            Coord(1, 2),
-            Coord(2, 0),
-            Coord(2, 1),
-            Coord(2, 2),
-        ]);
-        assert_eq!(Coord(0, 0).neighbors(), vec![
-            Coord(0, 1),
-            Coord(1, 0),
-            Coord(1, 1),
-        ]);
-        assert_eq!(Coord(9, 9).neighbors(), vec![
-            Coord(8, 8),
-            Coord(8, 9),
-            Coord(9, 8),
-        ]);
+    fn input_errors() {
+        assert_eq!(input_generator("5483143223\n274585471").err(), Some(ParseError::new(11, 2, 10, "10 cells per line")));
+        assert_eq!(input_generator("5483143223\n27458a4711").err(), Some(ParseError::new(11, 2, 6, "a digit")));
     }
 }
@@ -1,7 +1,9 @@
 use std::collections::{HashMap,HashSet};
 
+use crate::{parse::{self, ParseError}, puzzle::Puzzle, solve::Answer};
+
 #[derive(Debug, PartialEq)]
-struct Input {
+pub struct Input {
     map: HashMap<String, HashSet<String>>
 }
 
@@ -12,16 +14,18 @@ impl Input {
         }
     }
 
-    fn new(input: &str) -> Self {
+    fn new(input: &str) -> Result<Self, ParseError> {
         let mut map = Self { map: HashMap::new() };
 
-        for line in input.lines() {
-            let (p1, p2) = line.split_once('-').unwrap();
+        for (n, line) in parse::lines(input) {
+            let (p1, p2) = line.split_once('-')
+                .filter(|(p1, p2)| !p1.is_empty() && !p2.is_empty())
+                .ok_or_else(|| ParseError::new(12, n, 1, "a link between two caves (a-b)"))?;
             map.add_link(p1, p2);
             map.add_link(p2, p1);
         }
 
-        map
+        Ok(map)
     }
 
     pub fn paths(&self, small_twice: bool) -> Vec<Vec<String>> {
@@ -67,7 +71,7 @@ impl Input {
 
 #[allow(unused_variables)]
 #[aoc_generator(day12)]
-fn input_generator(input: &str) -> Input {
+fn input_generator(input: &str) -> Result<Input, ParseError> {
     Input::new(input)
 }
 
@@ -87,6 +91,36 @@ fn part2(input: &Input) -> Output {
     input.paths(true).len()
 }
 
+pub struct Day12;
+
+impl Puzzle for Day12 {
+    const DAY: u8 = 12;
+    const EXAMPLE: &'static str = "start-A
+start-b
+A-c
+A-b
+b-d
+A-end
+b-end";
+    const EXAMPLE_ANSWERS: [Option<Answer>; 2] = [Some(Answer::Number(10)), Some(Answer::Number(36))];
+
+    type Input = Input;
+    type Output1 = Output;
+    type Output2 = Output;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
 #[allow(unreachable_code)]
 #[cfg(test)]
 mod tests {
@@ -94,13 +128,7 @@ mod tests {
 
     fn exemple_raw_input(n: usize) -> &'static str {
         match n {
-            1 => "start-A
-start-b
-A-c
-A-b
-b-d
-A-end
-b-end",
+            1 => Day12::EXAMPLE,
             2 => "dc-end
 HN-start
 start-kj
@@ -135,21 +163,21 @@ start-RW",
 
     #[test]
     fn part2_provided_exemple() {
-        assert_eq!(part2(&input_generator(exemple_raw_input(1))), 36);
-        assert_eq!(part2(&input_generator(exemple_raw_input(2))), 103);
-        assert_eq!(part2(&input_generator(exemple_raw_input(3))), 3509);
+        assert_eq!(part2(&input_generator(exemple_raw_input(1)).unwrap()), 36);
+        assert_eq!(part2(&input_generator(exemple_raw_input(2)).unwrap()), 103);
+        assert_eq!(part2(&input_generator(exemple_raw_input(3)).unwrap()), 3509);
     }
 
     #[test]
     fn part1_provided_exemple() {
-        assert_eq!(part1(&input_generator(exemple_raw_input(1))), 10);
-        assert_eq!(part1(&input_generator(exemple_raw_input(2))), 19);
-        assert_eq!(part1(&input_generator(exemple_raw_input(3))), 226);
+        assert_eq!(part1(&input_generator(exemple_raw_input(1)).unwrap()), 10);
+        assert_eq!(part1(&input_generator(exemple_raw_input(2)).unwrap()), 19);
+        assert_eq!(part1(&input_generator(exemple_raw_input(3)).unwrap()), 226);
     }
 
     #[test]
     fn part1_provided_exemple_input() {
-        assert_eq!(input_generator(exemple_raw_input(1)), Input {
+        assert_eq!(input_generator(exemple_raw_input(1)).unwrap(), Input {
             map: HashMap::from([
                 ("start".to_string(), HashSet::from(["A".to_string(), "b".to_string()])),
                 ("A".to_string(), HashSet::from(["c".to_string(), "b".to_string(), "end".to_string()])),
@@ -159,4 +187,9 @@ start-RW",
             ]),
         });
     }
+
+    #[test]
+    fn input_errors() {
+        assert_eq!(input_generator("start-A\nA-\nA-end"), Err(ParseError::new(12, 2, 1, "a link between two caves (a-b)")));
+    }
 }
@@ -1,8 +1,9 @@
-use std::{collections::{HashSet, HashMap}, fmt::Display};
-use std::cmp::max;
+use std::{borrow::Cow, fmt::Display};
 
 use parse_display::FromStr;
 
+use crate::{grid::Grid, parse::{self, ParseError}, puzzle::Puzzle, solve::Answer};
+
 #[derive(PartialEq, Debug, FromStr)]
 #[display("{x},{y}")]
 struct Dot {
@@ -18,85 +19,70 @@ enum Fold {
     AlongY(usize),
 }
 
+// rows are the y coordinates and columns the x coordinates
 #[derive(Debug, PartialEq, Clone)]
 struct Paper {
-    dots: HashMap<usize, HashSet<usize>>,
-    width: usize,
-    height: usize,
+    dots: Grid<bool>,
 }
 
 impl Paper {
+    pub fn from_dots(dots: &[Dot]) -> Self {
+        let width = dots.iter().map(|dot| dot.x + 1).max().unwrap_or(1);
+        let height = dots.iter().map(|dot| dot.y + 1).max().unwrap_or(1);
+
+        let mut grid = Grid::from_fn(width, height, |_| false);
+
+        for dot in dots {
+            grid[(dot.y, dot.x)] = true;
+        }
+
+        Self { dots: grid }
+    }
+
     pub fn dots(&self) -> Vec<Dot> {
         self.dots
             .iter()
-            .map(|(x, ys)| ys
-                .iter()
-                .map(|y| Dot { x: *x, y: *y })
-            )
-            .flatten()
+            .filter(|(_, &dot)| dot)
+            .map(|((y, x), _)| Dot { x, y })
             .collect::<Vec<_>>()
     }
 }
 
 #[derive(Debug, PartialEq)]
-struct Input {
+pub struct Input {
     paper: Paper,
     folds: Vec<Fold>,
 }
 
 impl Display for Paper {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str(&(0..=self.height)
-            .map(|y| {
-                (0..=self.width)
-                    .map(|x| self.dots
-                        .get(&x)
-                        .map(|ys| ys.contains(&y))
-                        .unwrap_or(false)
-                    )
-                    .map(|exists| if exists { "#" } else { "." })
-                    .collect::<Vec<_>>()
-                    .join("")
-            })
-            .collect::<Vec<_>>()
-            .join("\n"))
+        self.dots.map(|&dot| if dot { '#' } else { '.' }).fmt(f)
     }
 }
 
 impl Input {
-    pub fn from(input: &str) -> Self {
-        let mut lines = input.lines();
-        let (dots, width, height) = lines.by_ref()
-            .take_while(|&line| !line.is_empty())
-            .map(|line| line.parse::<Dot>().unwrap())
-            .fold(
-                (HashMap::new(), 0, 0),
-                |(mut dots, width, height): (HashMap<usize, HashSet<_>>, _, _), dot| {
-                    dots.entry(dot.x).or_default().insert(dot.y);
-
-                    (dots, max(width, dot.x), max(height, dot.y))
-                },
-            );
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        let mut lines = parse::lines(input);
+        let dots = lines.by_ref()
+            .take_while(|&(_, line)| !line.is_empty())
+            .map(|line| parse::parse::<Dot>(13, line, line.1, "a dot (x,y)"))
+            .collect::<Result<Vec<_>, _>>()?;
 
         let folds = lines
-            .map(|str| str.parse::<Fold>().unwrap())
-            .collect::<Vec<_>>();
+            .map(|line| parse::parse(13, line, line.1, "a fold instruction (fold along x=n or y=n)"))
+            .collect::<Result<Vec<_>, _>>()?;
 
-        Self {
-            paper: Paper {
-                dots,
-                width,
-                height,
-            },
+        Ok(Self {
+            paper: Paper::from_dots(&dots),
             folds,
-        }
+        })
     }
 }
 
 #[allow(unused_variables)]
 #[aoc_generator(day13)]
-fn input_generator(input: &str) -> Input {
-    Input::from(input)
+fn input_generator(input: &str) -> Result<Input, ParseError> {
+    Input::parse(input)
 }
 
 type Output = usize;
@@ -105,27 +91,26 @@ type Output = usize;
 impl Paper {
     pub fn fold(&self, instruction: Fold) -> Self {
         let (width, height) = match instruction {
-            Fold::AlongX(x) => (x - 1, self.height),
-            Fold::AlongY(n) => (self.width, n - 1),
+            Fold::AlongX(n) => (n, self.dots.height()),
+            Fold::AlongY(n) => (self.dots.width(), n),
         };
 
-        let mut dots: HashMap<usize, HashSet<usize>> = HashMap::new();
+        let mut dots = Grid::from_fn(width, height, |_| false);
 
-        for (x, ys) in &self.dots {
-            for y in ys {
-                let (nx, ny) = match instruction {
-                    Fold::AlongX(n) => (if x > &n { 2 * n - x } else { *x }, *y),
-                    Fold::AlongY(n) => (*x, if y > &n { 2 * n - y } else { *y }),
-                };
+        for ((y, x), _) in self.dots.iter().filter(|(_, &dot)| dot) {
+            let (nx, ny) = match instruction {
+                Fold::AlongX(n) => (if x > n { 2 * n - x } else { x }, y),
+                Fold::AlongY(n) => (x, if y > n { 2 * n - y } else { y }),
+            };
 
-                dots.entry(nx).or_default().insert(ny);
+            // dots never appear on the fold lines
+            if let Some(dot) = dots.get_mut((ny, nx)) {
+                *dot = true;
             }
         }
 
         Self {
             dots,
-            width,
-            height,
         }
     }
 }
@@ -151,13 +136,11 @@ fn part2(input: &Input) -> String {
     )
 }
 
-#[allow(unreachable_code)]
-#[cfg(test)]
-mod tests {
-    use super::*;
+pub struct Day13;
 
-    fn exemple_raw_input() -> &'static str {
-        "6,10
+impl Puzzle for Day13 {
+    const DAY: u8 = 13;
+    const EXAMPLE: &'static str = "6,10
 0,14
 9,10
 0,3
@@ -177,12 +160,37 @@ mod tests {
 9,0
 
 fold along y=7
-fold along x=5"
+fold along x=5";
+    const EXAMPLE_ANSWERS: [Option<Answer>; 2] = [
+        Some(Answer::Number(17)),
+        Some(Answer::Text(Cow::Borrowed("#####\n#...#\n#...#\n#...#\n#####\n.....\n....."))),
+    ];
+
+    type Input = Input;
+    type Output1 = Output;
+    type Output2 = String;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
     }
 
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
+#[allow(unreachable_code)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     #[test]
     fn part2_provided_exemple() {
-        assert_eq!(part2(&input_generator(exemple_raw_input())), "#####
+        assert_eq!(part2(&input_generator(Day13::EXAMPLE).unwrap()), "#####
 #...#
 #...#
 #...#
@@ -193,32 +201,39 @@ fold along x=5"
 
     #[test]
     fn part1_provided_exemple() {
-        assert_eq!(part1(&input_generator(exemple_raw_input())), 17);
+        assert_eq!(part1(&input_generator(Day13::EXAMPLE).unwrap()), 17);
+    }
+
+    #[test]
+    fn paper_size() {
+        let paper = input_generator(Day13::EXAMPLE).unwrap().paper;
+
+        assert_eq!((paper.dots.width(), paper.dots.height()), (11, 15));
+
+        let folded = paper.fold(Fold::AlongY(7));
+
+        assert_eq!((folded.dots.width(), folded.dots.height()), (11, 7));
     }
 
     #[test]
     fn count_dots() {
-        assert_eq!(input_generator(exemple_raw_input()).paper.dots().len(), 18);
+        assert_eq!(input_generator(Day13::EXAMPLE).unwrap().paper.dots().len(), 18);
     }
 
     #[test]
     fn part1_provided_exemple_input() {
-        assert_eq!(input_generator(exemple_raw_input()), Input {
-            paper: Paper {
-                dots: HashMap::from([
-                    (0, HashSet::from([3, 13, 14])),
-                    (1, HashSet::from([10])),
-                    (2, HashSet::from([14])),
-                    (3, HashSet::from([0, 4])),
-                    (4, HashSet::from([1, 11])),
-                    (6, HashSet::from([0, 10, 12])),
-                    (8, HashSet::from([4, 10])),
-                    (9, HashSet::from([0, 10])),
-                    (10, HashSet::from([4, 12])),
-                ]),
-                width: 10,
-                height: 14,
-            },
+        assert_eq!(input_generator(Day13::EXAMPLE).unwrap(), Input {
+            paper: Paper::from_dots(&[
+                Dot { x: 0, y: 3 }, Dot { x: 0, y: 13 }, Dot { x: 0, y: 14 },
+                Dot { x: 1, y: 10 },
+                Dot { x: 2, y: 14 },
+                Dot { x: 3, y: 0 }, Dot { x: 3, y: 4 },
+                Dot { x: 4, y: 1 }, Dot { x: 4, y: 11 },
+                Dot { x: 6, y: 0 }, Dot { x: 6, y: 10 }, Dot { x: 6, y: 12 },
+                Dot { x: 8, y: 4 }, Dot { x: 8, y: 10 },
+                Dot { x: 9, y: 0 }, Dot { x: 9, y: 10 },
+                Dot { x: 10, y: 4 }, Dot { x: 10, y: 12 },
+            ]),
             folds: vec![
                 Fold::AlongY(7),
                 Fold::AlongX(5),
@@ -229,7 +244,7 @@ fold along x=5"
     #[test]
     fn provide_exemple_display() {
         assert_eq!(
-            format!("{}", input_generator(exemple_raw_input()).paper),
+            format!("{}", input_generator(Day13::EXAMPLE).unwrap().paper),
             "...#..#..#.
 ....#......
 ...........
@@ -247,4 +262,13 @@ fold along x=5"
 #.#........"
         )
     }
+
+    #[test]
+    fn input_errors() {
+        assert_eq!(input_generator("6,10\n0;14").err(), Some(ParseError::new(13, 2, 1, "a dot (x,y)")));
+        assert_eq!(
+            input_generator("6,10\n\nfold along y=7\nfold along z=5").err(),
+            Some(ParseError::new(13, 4, 1, "a fold instruction (fold along x=n or y=n)")),
+        );
+    }
 }
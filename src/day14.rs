@@ -1,5 +1,7 @@
 use std::{collections::HashMap, usize::MAX};
 
+use crate::{parse::{self, ParseError}, puzzle::Puzzle, solve::Answer};
+
 type PolymerElement = char;
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
@@ -45,7 +47,7 @@ impl Polymer {
 }
 
 #[derive(Debug, PartialEq)]
-struct Input {
+pub struct Input {
     template: Polymer,
     pair_insertion_rules: HashMap<PolymerElementsPair, PolymerElement>,
 }
@@ -62,12 +64,15 @@ impl PolymerElementsPair {
 
 #[allow(unused_variables)]
 #[aoc_generator(day14)]
-fn input_generator(input: &str) -> Input {
-    let mut lines = input.lines();
-    let line = lines.next().unwrap();
+fn input_generator(input: &str) -> Result<Input, ParseError> {
+    let mut lines = parse::lines(input);
+    let line = lines.next()
+        .map(|(_, line)| line)
+        .filter(|line| !line.is_empty())
+        .ok_or_else(|| ParseError::new(14, 1, 1, "a polymer template"))?;
 
     let template = Polymer {
-        count_start: line.chars().nth(0).unwrap(),
+        count_start: line.chars().next().unwrap(),
         pairs: line
             .chars()
             .collect::<Vec<_>>()
@@ -78,24 +83,25 @@ fn input_generator(input: &str) -> Input {
                 polymer
             }),
     };
-    lines.next();
 
-    Input {
-        template,
-        pair_insertion_rules: lines.fold(
-            HashMap::new(),
-            |mut rules, line| {
-                let (pair, char) = line.split_once(" -> ").unwrap();
+    match lines.next() {
+        Some((_, "")) => {},
+        Some((n, _)) => return Err(ParseError::new(14, n, 1, "an empty line")),
+        None => return Err(ParseError::new(14, 2, 1, "an empty line")),
+    }
 
-                rules.insert(
+    Ok(Input {
+        template,
+        pair_insertion_rules: lines
+            .map(|(n, line)| match line.split_once(" -> ") {
+                Some((pair, char)) if pair.chars().count() == 2 && char.chars().count() == 1 => Ok((
                     PolymerElementsPair::from_str(pair),
                     char.chars().next().unwrap(),
-                );
-
-                rules
-            },
-        ),
-    }
+                )),
+                _ => Err(ParseError::new(14, n, 1, "a pair insertion rule (AB -> C)")),
+            })
+            .collect::<Result<HashMap<_, _>, _>>()?,
+    })
 }
 
 type Output = usize;
@@ -134,13 +140,11 @@ fn part2(input: &Input) -> Output {
     max - min
 }
 
-#[allow(unreachable_code)]
-#[cfg(test)]
-mod tests {
-    use super::*;
+pub struct Day14;
 
-    fn exemple_raw_input() -> &'static str {
-        "NNCB
+impl Puzzle for Day14 {
+    const DAY: u8 = 14;
+    const EXAMPLE: &'static str = "NNCB
 
 CH -> B
 HH -> N
@@ -157,17 +161,39 @@ BN -> B
 BB -> N
 BC -> B
 CC -> N
-CN -> C"
+CN -> C";
+    const EXAMPLE_ANSWERS: [Option<Answer>; 2] = [Some(Answer::Number(1588)), Some(Answer::Number(2188189693529))];
+
+    type Input = Input;
+    type Output1 = Output;
+    type Output2 = Output;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
     }
 
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
+#[allow(unreachable_code)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     #[test]
     fn part2_provided_exemple() {
-        assert_eq!(part2(&input_generator(exemple_raw_input())), 2188189693529);
+        assert_eq!(part2(&input_generator(Day14::EXAMPLE).unwrap()), 2188189693529);
     }
 
     #[test]
     fn part1_provided_exemple() {
-        assert_eq!(part1(&input_generator(exemple_raw_input())), 1588);
+        assert_eq!(part1(&input_generator(Day14::EXAMPLE).unwrap()), 1588);
     }
 
     #[test]
@@ -190,7 +216,7 @@ CN -> C"
 
     #[test]
     fn part1_provided_exemple_input() {
-        assert_eq!(input_generator(exemple_raw_input()), Input {
+        assert_eq!(input_generator(Day14::EXAMPLE).unwrap(), Input {
             template: Polymer {
                 count_start: 'N',
                 pairs: HashMap::from([
@@ -219,4 +245,14 @@ CN -> C"
             ]),
         });
     }
+
+    #[test]
+    fn input_errors() {
+        assert_eq!(input_generator("").err(), Some(ParseError::new(14, 1, 1, "a polymer template")));
+        assert_eq!(input_generator("NNCB\nCH -> B").err(), Some(ParseError::new(14, 2, 1, "an empty line")));
+        assert_eq!(
+            input_generator("NNCB\n\nCH -> B\nHHH -> N").err(),
+            Some(ParseError::new(14, 4, 1, "a pair insertion rule (AB -> C)")),
+        );
+    }
 }
@@ -1,159 +1,224 @@
-use std::{collections::{HashMap, BinaryHeap}, cmp::Ordering};
+use std::{cmp::Reverse, collections::{BinaryHeap, HashSet}, fmt::Display};
+
+use crate::{grid::{Grid, Pos}, parse::ParseError, puzzle::Puzzle, search::{self, Path}, solve::Answer};
 
 #[derive(Debug, PartialEq)]
-struct Input {
-    risks: Vec<u32>,
-    width: usize,
-    height: usize,
-    unfolded: usize,
+pub struct Input {
+    // risks are between 1 and 9, bytes keep big unfolded caves small
+    risks: Grid<u8>,
 }
 
-#[derive(Debug, PartialEq, Eq)]
-struct PathRisk {
-    path_end: usize,
-    risk: u32,
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Mode {
+    // A* from the search module, keeping the risks in a HashMap
+    Generic,
+    // Dial's algorithm: steps cost between 1 and 9 so a ring of 10 buckets,
+    // indexed by total risk, can replace the priority queue
+    BucketQueue,
+    // A* on a flat vector of total risks
+    AStar,
 }
 
-impl PartialOrd for PathRisk {
-    fn lt(&self, other: &Self) -> bool {
-        !self.risk.lt(&other.risk)
-    }
-
-    fn le(&self, other: &Self) -> bool {
-        !self.risk.le(&other.risk)
-    }
+#[allow(unused_variables)]
+#[aoc_generator(day15)]
+fn input_generator(input: &str) -> Result<Input, ParseError> {
+    Ok(Input {
+        risks: Grid::parse(15, input, "a risk level from 1 to 9", |c| c.to_digit(10).filter(|&risk| risk > 0).map(|risk| risk as u8))?,
+    })
+}
 
-    fn gt(&self, other: &Self) -> bool {
-        !self.risk.gt(&other.risk)
+impl Input {
+    pub fn end(&self) -> Pos {
+        (self.risks.height() - 1, self.risks.width() - 1)
     }
 
-    fn ge(&self, other: &Self) -> bool {
-        !self.risk.ge(&other.risk)
+    pub fn cells(&self) -> usize {
+        self.risks.len()
     }
 
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.risk.partial_cmp(&other.risk).map(Ordering::reverse)
+    pub fn at(&self, pos: Pos) -> u32 {
+        u32::from(self.risks[pos])
     }
-}
 
-impl Ord for PathRisk {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.risk.cmp(&other.risk).reverse()
+    fn index(&self, (row, column): Pos) -> usize {
+        row * self.risks.width() + column
     }
-}
 
-#[allow(unused_variables)]
-#[aoc_generator(day15)]
-fn input_generator(input: &str) -> Input {
-    let lines = input.lines();
+    // each step costs at least 1, so it never overestimates the risk left
+    fn distance_to_end(&self, (row, column): Pos) -> u32 {
+        let end = self.end();
 
-    Input {
-        risks: input.lines()
-            .flat_map(|line| line.chars().map(|c| c.to_digit(10).unwrap()))
-            .collect::<Vec<_>>(),
-        width: input.lines().next().unwrap().len(),
-        height: input.lines().collect::<Vec<_>>().len(),
-        unfolded: 1,
+        (end.0 - row + end.1 - column) as u32
     }
-}
 
-impl Input {
-    pub fn unfolded_width(&self) -> usize {
-        self.unfolded * self.width
+    // the risk of the starting position isn't counted since we never enter it
+    pub fn safest_path(&self) -> Path<Pos, u32> {
+        self.safest_path_with(Mode::BucketQueue)
     }
 
-    pub fn unfolded_height(&self) -> usize {
-        self.unfolded * self.height
+    pub fn safest_path_with(&self, mode: Mode) -> Path<Pos, u32> {
+        match mode {
+            Mode::Generic => search::astar(
+                (0, 0),
+                |&pos| self.risks.neighbors4(pos).map(|next| (next, self.at(next))),
+                |&pos| self.distance_to_end(pos),
+                |&pos| pos == self.end(),
+            ).expect("the end is always reachable"),
+            Mode::BucketQueue => self.path_from(&self.bucket_queue_risks()),
+            Mode::AStar => self.path_from(&self.astar_risks()),
+        }
     }
 
-    pub fn end(&self) -> usize {
-        self.unfolded_height() * self.unfolded_width() - 1
-    }
+    // lowest total risk of the positions, at least up to the end, u32::MAX for the others
+    fn bucket_queue_risks(&self) -> Vec<u32> {
+        let end = self.end();
+        let mut risks = vec![u32::MAX; self.risks.len()];
+        let mut buckets = vec![vec![]; 10];
+        let mut queued = 1;
 
-    pub fn at(&self, pos: usize) -> u32 {
-        let pos = self.pos(pos);
-        let (x, xf) = (pos.0 % self.width, pos.0 / self.width);
-        let (y, yf) = (pos.1 % self.height, pos.1 / self.height);
+        risks[0] = 0;
+        buckets[0].push((0, 0));
+
+        let mut risk = 0;
+
+        while queued > 0 {
+            // next positions are at most 9 buckets away so they never end up in this one
+            while let Some(pos) = buckets[risk as usize % 10].pop() {
+                queued -= 1;
+
+                // it's been queued again with a lower risk
+                if risks[self.index(pos)] != risk {
+                    continue;
+                }
+
+                if pos == end {
+                    return risks;
+                }
 
-        let v = self.risks[x + y * self.width] + u32::try_from(xf).unwrap() + u32::try_from(yf).unwrap();
+                for next in self.risks.neighbors4(pos) {
+                    let next_risk = risk + self.at(next);
+                    let next_index = self.index(next);
 
-        if v > 9 {
-            v % 10 + 1
-        } else {
-            v
+                    if next_risk < risks[next_index] {
+                        risks[next_index] = next_risk;
+                        buckets[next_risk as usize % 10].push(next);
+                        queued += 1;
+                    }
+                }
+            }
+
+            risk += 1;
         }
-    }
 
-    pub fn shortest_path_length(&self) -> u32 {
-        let mut shortest_paths: HashMap<usize, u32> = HashMap::from([(0, 0)]);
+        risks
+    }
 
+    fn astar_risks(&self) -> Vec<u32> {
         let end = self.end();
-        let mut buffer = BinaryHeap::from([PathRisk {
-            path_end: 0,
-            risk: 0,
-        }]);
-
-        while let Some(p) = buffer.pop() {
-            let i = p.path_end;
-            let w = *shortest_paths.get(&i).unwrap();
-
-            for neighbor in self.neighbor_of(i) {
-                let risk = self.at(neighbor);
-
-                let e = shortest_paths
-                    .entry(neighbor)
-                    .or_insert(u32::MAX);
-
-                if *e > w + risk {
-                    buffer.push(PathRisk {
-                        path_end: neighbor,
-                        risk: w + risk,
-                    });
-                    *e = w + risk;
+        let mut risks = vec![u32::MAX; self.risks.len()];
+        let mut queue = BinaryHeap::from([Reverse((self.distance_to_end((0, 0)), 0, (0, 0)))]);
+
+        risks[0] = 0;
+
+        while let Some(Reverse((_, risk, pos))) = queue.pop() {
+            if risk > risks[self.index(pos)] {
+                continue;
+            }
+
+            if pos == end {
+                break;
+            }
+
+            for next in self.risks.neighbors4(pos) {
+                let next_risk = risk + self.at(next);
+                let next_index = self.index(next);
+
+                if next_risk < risks[next_index] {
+                    risks[next_index] = next_risk;
+                    queue.push(Reverse((next_risk + self.distance_to_end(next), next_risk, next)));
                 }
             }
         }
 
-        *shortest_paths.get(&end).unwrap()
+        risks
     }
 
-    fn pos(&self, i: usize) -> (usize, usize) {
-        (i % self.unfolded_width(), i / self.unfolded_width())
-    }
+    // walks back from the end, through the positions we could have come from
+    fn path_from(&self, risks: &[u32]) -> Path<Pos, u32> {
+        let mut pos = self.end();
+        let mut states = vec![pos];
 
-    fn neighbor_of(&self, i: usize) -> Vec<usize> {
-        let (x, y) = self.pos(i);
-        let mut neighbors = vec![];
+        while pos != (0, 0) {
+            let risk = risks[self.index(pos)] - self.at(pos);
 
-        if x > 0 {
-            neighbors.push(self.unfolded_width() * y + x - 1);
-        }
+            pos = self.risks.neighbors4(pos)
+                .find(|&previous| risks[self.index(previous)] == risk)
+                .expect("a position on the path comes from one of its neighbors");
 
-        if x < self.unfolded_width() - 1 {
-            neighbors.push(self.unfolded_width() * y + x + 1);
+            states.push(pos);
         }
 
-        if y > 0 {
-            neighbors.push(self.unfolded_width() * y + x - self.unfolded_width());
-        }
+        states.reverse();
 
-        if y < self.unfolded_height() - 1 {
-            neighbors.push(self.unfolded_width() * y + x + self.unfolded_width());
+        Path {
+            cost: risks[self.index(self.end())],
+            states,
         }
+    }
 
-        neighbors
+    pub fn shortest_path_length(&self) -> u32 {
+        self.safest_path().cost
     }
 
-    fn unfold(&self, times: usize) -> Self {
+    pub fn path_view(&self, path: &[Pos]) -> PathView<'_> {
+        PathView {
+            input: self,
+            path: path.iter().cloned().collect(),
+        }
+    }
+
+    // the map is repeated `times` times in both directions, each repetition
+    // increasing the risks by 1 (wrapping back to 1 after 9)
+    pub fn unfold(&self, times: usize) -> Self {
+        let (width, height) = (self.risks.width(), self.risks.height());
+
         Self {
-            risks: self.risks.clone(),
-            width: self.width,
-            height: self.height,
-            unfolded: self.unfolded * times,
+            risks: Grid::from_fn(width * times, height * times, |(row, column)| {
+                let v = usize::from(self.risks[(row % height, column % width)]) + row / height + column / width;
+
+                ((v - 1) % 9 + 1) as u8
+            }),
         }
     }
 }
 
+// displays the cave with the risks on the path in bold, or with `{:#}`
+// only the risks on the path, without terminal escape codes
+pub struct PathView<'a> {
+    input: &'a Input,
+    path: HashSet<Pos>,
+}
+
+impl Display for PathView<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (row, risks) in self.input.risks.rows().enumerate() {
+            if row > 0 {
+                writeln!(f)?;
+            }
+
+            for (column, risk) in risks.iter().enumerate() {
+                match (self.path.contains(&(row, column)), f.alternate()) {
+                    (true, false) => write!(f, "\x1b[1m{}\x1b[0m", risk)?,
+                    (false, true) => write!(f, ".")?,
+                    _ => write!(f, "{}", risk)?,
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
 type Output = u32;
 
 #[allow(unused_variables)]
@@ -170,13 +235,11 @@ fn part2(input: &Input) -> Output {
     input.unfold(5).shortest_path_length()
 }
 
-#[allow(unreachable_code)]
-#[cfg(test)]
-mod tests {
-    use super::*;
+pub struct Day15;
 
-    fn exemple_raw_input() -> &'static str {
-        "1163751742
+impl Puzzle for Day15 {
+    const DAY: u8 = 15;
+    const EXAMPLE: &'static str = "1163751742
 1381373672
 2136511328
 3694931569
@@ -185,23 +248,45 @@ mod tests {
 1359912421
 3125421639
 1293138521
-2311944581"
+2311944581";
+    const EXAMPLE_ANSWERS: [Option<Answer>; 2] = [Some(Answer::Number(40)), Some(Answer::Number(315))];
+
+    type Input = Input;
+    type Output1 = Output;
+    type Output2 = Output;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
     }
+}
+
+#[allow(unreachable_code)]
+#[cfg(test)]
+mod tests {
+    use super::*;
 
     #[test]
     fn part2_provided_exemple() {
-        assert_eq!(part2(&input_generator(exemple_raw_input())), 315);
+        assert_eq!(part2(&input_generator(Day15::EXAMPLE).unwrap()), 315);
     }
 
     #[test]
     fn part1_provided_exemple() {
-        assert_eq!(part1(&input_generator(exemple_raw_input())), 40);
+        assert_eq!(part1(&input_generator(Day15::EXAMPLE).unwrap()), 40);
     }
 
     #[test]
     fn part1_provided_exemple_input() {
-        assert_eq!(input_generator(exemple_raw_input()), Input {
-            risks: vec![
+        assert_eq!(input_generator(Day15::EXAMPLE).unwrap(), Input {
+            risks: Grid::new(10, vec![
                 1, 1, 6, 3, 7, 5, 1, 7, 4, 2,
                 1, 3, 8, 1, 3, 7, 3, 6, 7, 2,
                 2, 1, 3, 6, 5, 1, 1, 3, 2, 8,
@@ -212,10 +297,84 @@ mod tests {
                 3, 1, 2, 5, 4, 2, 1, 6, 3, 9,
                 1, 2, 9, 3, 1, 3, 8, 5, 2, 1,
                 2, 3, 1, 1, 9, 4, 4, 5, 8, 1,
-            ],
-            width: 10,
-            height: 10,
-            unfolded: 1,
+            ]),
         });
     }
+
+    #[test]
+    fn safest_path() {
+        for input in [input_generator(Day15::EXAMPLE).unwrap(), input_generator(Day15::EXAMPLE).unwrap().unfold(5)] {
+            for mode in [Mode::Generic, Mode::BucketQueue, Mode::AStar] {
+                let path = input.safest_path_with(mode);
+
+                assert_eq!(path.states.first(), Some(&(0, 0)));
+                assert_eq!(path.states.last(), Some(&input.end()));
+                assert_eq!(path.states.iter().skip(1).map(|&pos| input.at(pos)).sum::<u32>(), path.cost);
+                assert!(path.states.windows(2).all(|step| input.risks.neighbors4(step[0]).any(|pos| pos == step[1])));
+            }
+
+            // without the heuristic we get the same risk
+            let dijkstra = search::dijkstra(
+                (0, 0),
+                |&pos| input.risks.neighbors4(pos).map(|next| (next, input.at(next))),
+                |&pos| pos == input.end(),
+            ).unwrap();
+
+            assert_eq!(dijkstra.cost, input.safest_path_with(Mode::Generic).cost);
+            assert_eq!(dijkstra.cost, input.safest_path_with(Mode::BucketQueue).cost);
+            assert_eq!(dijkstra.cost, input.safest_path_with(Mode::AStar).cost);
+        }
+    }
+
+    #[test]
+    fn large_unfold() {
+        let input = input_generator(Day15::EXAMPLE).unwrap().unfold(50);
+
+        assert_eq!((input.risks.width(), input.risks.height()), (500, 500));
+        assert_eq!(input.at((499, 499)), 9);
+        assert_eq!(input.safest_path_with(Mode::BucketQueue).cost, input.safest_path_with(Mode::AStar).cost);
+    }
+
+    #[test]
+    fn path_view() {
+        let input = input_generator(Day15::EXAMPLE).unwrap();
+        let path = input.safest_path();
+
+        // the same path as in the puzzle description
+        assert_eq!(format!("{:#}", input.path_view(&path.states)), "1.........
+1.........
+2136511...
+......15..
+.......11.
+........3.
+........2.
+........3.
+........21
+.........1");
+
+        let input = input_generator("19\n11").unwrap();
+        let path = input.safest_path();
+
+        assert_eq!(path.states, [(0, 0), (1, 0), (1, 1)]);
+        assert_eq!(format!("{}", input.path_view(&path.states)), "\x1b[1m1\x1b[0m9\n\x1b[1m1\x1b[0m\x1b[1m1\x1b[0m");
+    }
+
+    #[test]
+    fn unfold() {
+        let input = input_generator("8").unwrap().unfold(5);
+
+        assert_eq!(format!("{}", input.risks), "89123
+91234
+12345
+23456
+34567");
+    }
+
+    #[test]
+    fn input_errors() {
+        assert_eq!(input_generator(""), Err(ParseError::new(15, 1, 1, "a risk level from 1 to 9")));
+        assert_eq!(input_generator("12\n30"), Err(ParseError::new(15, 2, 2, "a risk level from 1 to 9")));
+        assert_eq!(input_generator("116\n13\n213"), Err(ParseError::new(15, 2, 3, "3 cells per line")));
+        assert_eq!(input_generator("116\n1381"), Err(ParseError::new(15, 2, 4, "3 cells per line")));
+    }
 }
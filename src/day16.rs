@@ -1,38 +1,236 @@
+use std::io::{self, Read};
+
+use crate::{parse::ParseError, puzzle::Puzzle, solve::Answer};
+
 #[derive(Debug, PartialEq, Clone)]
-struct Input {
-    bits: Vec<bool>,
+pub struct Input {
+    bytes: Vec<u8>,
+    // in bits
+    length: usize,
 }
 
-#[derive(Debug, PartialEq)]
-enum Packet {
+#[derive(Debug, PartialEq, Clone)]
+pub enum Packet {
     Literal {
         version: usize,
         value: usize,
     },
     Operator {
         version: usize,
-        type_id: usize,
+        operator: Operator,
         packets: Vec<Packet>,
     },
 }
 
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Operator {
+    Sum,
+    Product,
+    Minimum,
+    Maximum,
+    GreaterThan,
+    LessThan,
+    EqualTo,
+}
+
+impl Operator {
+    // 4 is the type ID of literals
+    pub fn from_type_id(type_id: usize) -> Option<Self> {
+        match type_id {
+            0 => Some(Operator::Sum),
+            1 => Some(Operator::Product),
+            2 => Some(Operator::Minimum),
+            3 => Some(Operator::Maximum),
+            5 => Some(Operator::GreaterThan),
+            6 => Some(Operator::LessThan),
+            7 => Some(Operator::EqualTo),
+            _ => None,
+        }
+    }
+
+    pub fn type_id(self) -> usize {
+        match self {
+            Operator::Sum => 0,
+            Operator::Product => 1,
+            Operator::Minimum => 2,
+            Operator::Maximum => 3,
+            Operator::GreaterThan => 5,
+            Operator::LessThan => 6,
+            Operator::EqualTo => 7,
+        }
+    }
+
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Operator::Sum => "+",
+            Operator::Product => "*",
+            Operator::Minimum => "min",
+            Operator::Maximum => "max",
+            Operator::GreaterThan => ">",
+            Operator::LessThan => "<",
+            Operator::EqualTo => "==",
+        }
+    }
+
+    // comparisons take exactly 2 sub-packets, the others at least 1
+    fn accepts(self, sub_packets: usize) -> bool {
+        match self {
+            Operator::GreaterThan | Operator::LessThan | Operator::EqualTo => sub_packets == 2,
+            _ => sub_packets > 0,
+        }
+    }
+}
+
 pub fn to_usize(bits: &[bool]) -> usize {
     bits.iter().fold(0, |n, &bit| (n * 2) + (if bit { 1 } else { 0 }))
 }
 
-pub fn read_literal_value(bits: &[bool]) -> (usize, usize) {
-    let mut pos = 0;
-    let mut n = 0;
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct BitsError {
+    // counted from the first bit of the transmission
+    pub offset: usize,
+    pub expected: String,
+}
+
+impl std::fmt::Display for BitsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "bit {}: expected {}", self.offset, self.expected)
+    }
+}
+
+impl std::error::Error for BitsError {}
+
+// reads bits most significant first, from bytes or from the hexadecimal digits of a
+// transmission. the source is read one byte at a time, so files are better buffered
+pub struct BitReader<R> {
+    source: R,
+    hex: bool,
+    // the bits of the last byte (or digit) not read yet, in the low bits
+    buffer: u8,
+    buffered: usize,
+    position: usize,
+    // reads can't go past it, it's moved to the end of the sub-packets while reading them
+    limit: usize,
+    ended: bool,
+}
+
+impl<R: Read> BitReader<R> {
+    pub fn new(source: R) -> Self {
+        BitReader { source, hex: false, buffer: 0, buffered: 0, position: 0, limit: usize::MAX, ended: false }
+    }
+
+    // whitespace ends the transmission
+    pub fn hex(source: R) -> Self {
+        BitReader { hex: true, ..BitReader::new(source) }
+    }
+
+    // when the source has more bits than the transmission, like a half byte for an odd number of digits
+    pub fn with_limit(self, limit: usize) -> Self {
+        BitReader { limit, ..self }
+    }
+
+    // the number of bits read so far
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    // the previous limit
+    fn set_limit(&mut self, limit: usize) -> usize {
+        std::mem::replace(&mut self.limit, limit)
+    }
+
+    // `UnexpectedEof` when there are less than `n` bits left
+    pub fn read_bits(&mut self, n: usize) -> io::Result<usize> {
+        assert!(n <= usize::BITS as usize, "can't read more than {} bits at once", usize::BITS);
+
+        if n > self.limit - self.position {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+
+        let mut value = 0;
+        let mut left = n;
+
+        while left > 0 {
+            if self.buffered == 0 && !self.fill()? {
+                return Err(io::ErrorKind::UnexpectedEof.into());
+            }
+
+            let taken = left.min(self.buffered);
+
+            self.buffered -= taken;
+            value = (value << taken) | (usize::from(self.buffer >> self.buffered) & ((1 << taken) - 1));
+            self.position += taken;
+            left -= taken;
+        }
+
+        Ok(value)
+    }
+
+    // false at the end of the source
+    fn fill(&mut self) -> io::Result<bool> {
+        let mut byte = [0];
+
+        if self.ended {
+            return Ok(false);
+        }
+
+        match self.source.read_exact(&mut byte) {
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => {
+                self.ended = true;
+
+                return Ok(false);
+            },
+            result => result?,
+        }
+
+        (self.buffer, self.buffered) = match (self.hex, char::from(byte[0]).to_digit(16)) {
+            (false, _) => (byte[0], 8),
+            (true, Some(digit)) => (digit as u8, 4),
+            (true, None) if byte[0].is_ascii_whitespace() => {
+                self.ended = true;
+
+                return Ok(false);
+            },
+            (true, None) => return Err(io::Error::new(io::ErrorKind::InvalidData, "an hexadecimal digit")),
+        };
+
+        Ok(true)
+    }
+}
+
+// the next `length` bits
+fn read<R: Read>(reader: &mut BitReader<R>, length: usize, expected: &str) -> Result<usize, BitsError> {
+    let offset = reader.position();
+
+    reader.read_bits(length).map_err(|e| match e.kind() {
+        io::ErrorKind::UnexpectedEof => BitsError { offset, expected: format!("{} ({} bits)", expected, length) },
+        // an invalid digit, right where the reader stopped
+        io::ErrorKind::InvalidData => BitsError { offset: reader.position(), expected: e.to_string() },
+        _ => BitsError { offset, expected: format!("{} ({} bits), reading failed: {}", expected, length, e) },
+    })
+}
+
+// literals are kept as a `usize`, larger ones are rejected rather than truncated
+// (leading zero groups don't count)
+pub fn read_literal_value<R: Read>(reader: &mut BitReader<R>) -> Result<usize, BitsError> {
+    let mut n: usize = 0;
 
     loop {
-        let (first, nexts) = bits[pos..pos+5].split_first().unwrap();
+        let offset = reader.position();
+        let group = read(reader, 5, "a literal value group")?;
 
-        n <<= 4;
-        n += to_usize(nexts);
-        pos += 5;
+        if n.leading_zeros() < 4 {
+            return Err(BitsError { offset, expected: format!("a literal value of at most {} bits", usize::BITS) });
+        }
 
-        if !*first {
-            return (n, pos);
+        n = (n << 4) + (group & 0b1111);
+
+        if group & 0b10000 == 0 {
+            return Ok(n);
         }
     }
 }
@@ -46,7 +244,7 @@ impl Packet {
             } => *version,
             Packet::Operator {
                 version,
-                type_id: _type_id,
+                operator: _operator,
                 packets
             } => packets.iter()
                 .map(|packet| packet.sum_of_versions())
@@ -54,112 +252,493 @@ impl Packet {
         }
     }
 
-    pub fn eval(&self) -> usize {
+    // checked, the packets of the transmissions fit in a `usize`
+    pub fn eval(&self) -> Result<usize, Overflow> {
+        self.eval_with()
+    }
+
+    // exact, however large the sums and products get, but the literals themselves
+    // are at most 64 bits: `decode` rejects larger ones
+    pub fn eval_big(&self) -> BigValue {
+        self.eval_with().expect("big values don't overflow")
+    }
+
+    pub fn eval_with<V: Value>(&self) -> Result<V, Overflow> {
+        self.eval_at(&mut vec![])
+    }
+
+    // `path` holds the indexes of the sub-packets leading to this one
+    fn eval_at<V: Value>(&self, path: &mut Vec<usize>) -> Result<V, Overflow> {
+        let (operator, packets) = match self {
+            Packet::Literal { value, .. } => return Ok(V::literal(*value)),
+            Packet::Operator { operator, packets, .. } => (operator, packets),
+        };
+
+        let mut values = Vec::with_capacity(packets.len());
+
+        for (index, packet) in packets.iter().enumerate() {
+            path.push(index);
+            values.push(packet.eval_at(path)?);
+            path.pop();
+        }
+
+        let overflow = || Overflow { path: path.clone(), packet: self.clone() };
+        let mut values = values.into_iter();
+
+        Ok(match operator {
+            Operator::Sum => values.try_fold(V::literal(0), |sum, value| sum.try_add(&value)).ok_or_else(overflow)?,
+            Operator::Product => values.try_fold(V::literal(1), |product, value| product.try_mul(&value)).ok_or_else(overflow)?,
+            Operator::Minimum => values.min().unwrap(),
+            Operator::Maximum => values.max().unwrap(),
+            Operator::GreaterThan => V::literal((values.next().unwrap() > values.next().unwrap()) as usize),
+            Operator::LessThan => V::literal((values.next().unwrap() < values.next().unwrap()) as usize),
+            Operator::EqualTo => V::literal((values.next().unwrap() == values.next().unwrap()) as usize),
+        })
+    }
+
+    // with the versions after `@`, like `(1@6 < 2@2)@1`
+    pub fn infix(&self) -> String {
         match self {
-            Packet::Literal {
-                version: _version,
-                value
-            } => *value,
-            Packet::Operator {
-                version: _version,
-                type_id,
-                packets
-            } => {
-                let mut values = packets.iter().map(Packet::eval);
-
-                match type_id {
-                    0 => values.sum(),
-                    1 => values.product(),
-                    2 => values.min().unwrap(),
-                    3 => values.max().unwrap(),
-                    5 => (values.next().unwrap() > values.next().unwrap()) as usize,
-                    6 => (values.next().unwrap() < values.next().unwrap()) as usize,
-                    7 => (values.next().unwrap() == values.next().unwrap()) as usize,
-                    _ => panic!("{}", type_id),
+            Packet::Literal { version, value } => format!("{}@{}", value, version),
+            Packet::Operator { version, operator, packets } => {
+                let operands = packets.iter().map(Packet::infix).collect::<Vec<_>>();
+
+                match operator {
+                    Operator::Minimum | Operator::Maximum => format!("{}({})@{}", operator.symbol(), operands.join(", "), version),
+                    _ => format!("({})@{}", operands.join(&format!(" {} ", operator.symbol())), version),
                 }
             },
         }
     }
+
+    // with the versions after `@`, like `(<@1 1@6 2@2)`
+    pub fn sexpr(&self) -> String {
+        match self {
+            Packet::Literal { version, value } => format!("{}@{}", value, version),
+            Packet::Operator { version, operator, packets } => format!(
+                "({}@{}{})",
+                operator.symbol(),
+                version,
+                packets.iter().map(|packet| format!(" {}", packet.sexpr())).collect::<String>(),
+            ),
+        }
+    }
+}
+
+// what packets evaluate to, `None` when the result doesn't fit
+pub trait Value: Clone + Ord {
+    fn literal(value: usize) -> Self;
+    fn try_add(&self, other: &Self) -> Option<Self>;
+    fn try_mul(&self, other: &Self) -> Option<Self>;
+}
+
+impl Value for usize {
+    fn literal(value: usize) -> Self {
+        value
+    }
+
+    fn try_add(&self, other: &Self) -> Option<Self> {
+        self.checked_add(*other)
+    }
+
+    fn try_mul(&self, other: &Self) -> Option<Self> {
+        self.checked_mul(*other)
+    }
+}
+
+// an unsigned integer of any size
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct BigValue {
+    // least significant first, without leading zeros
+    limbs: Vec<u32>,
 }
 
-fn read_packets(bits: &[bool]) -> (Vec<Packet>, usize) {
+impl BigValue {
+    fn normalized(mut limbs: Vec<u32>) -> Self {
+        while limbs.last() == Some(&0) {
+            limbs.pop();
+        }
+
+        BigValue { limbs }
+    }
+
+    pub fn to_usize(&self) -> Option<usize> {
+        if self.limbs.len() > 2 {
+            return None;
+        }
+
+        usize::try_from(self.limbs.iter().rev().fold(0, |n: u64, &limb| (n << 32) | u64::from(limb))).ok()
+    }
+}
+
+impl From<usize> for BigValue {
+    fn from(value: usize) -> Self {
+        BigValue::normalized((0..usize::BITS).step_by(32).map(|shift| (value >> shift) as u32).collect())
+    }
+}
+
+impl Ord for BigValue {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.limbs.len().cmp(&other.limbs.len())
+            .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigValue {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Value for BigValue {
+    fn literal(value: usize) -> Self {
+        BigValue::from(value)
+    }
+
+    fn try_add(&self, other: &Self) -> Option<Self> {
+        let limb = |value: &BigValue, index| u64::from(value.limbs.get(index).copied().unwrap_or(0));
+        let mut limbs = vec![];
+        let mut carry = 0;
+
+        for index in 0..self.limbs.len().max(other.limbs.len()) {
+            let n = limb(self, index) + limb(other, index) + carry;
+
+            limbs.push(n as u32);
+            carry = n >> 32;
+        }
+
+        limbs.push(carry as u32);
+
+        Some(BigValue::normalized(limbs))
+    }
+
+    fn try_mul(&self, other: &Self) -> Option<Self> {
+        let mut limbs = vec![0; self.limbs.len() + other.limbs.len()];
+
+        for (i, &a) in self.limbs.iter().enumerate() {
+            let mut carry = 0;
+
+            for (j, &b) in other.limbs.iter().enumerate() {
+                // at most (2^32 - 1)^2 + 2 * (2^32 - 1) = 2^64 - 1
+                let n = u64::from(limbs[i + j]) + u64::from(a) * u64::from(b) + carry;
+
+                limbs[i + j] = n as u32;
+                carry = n >> 32;
+            }
+
+            limbs[i + other.limbs.len()] = carry as u32;
+        }
+
+        Some(BigValue::normalized(limbs))
+    }
+}
+
+impl std::fmt::Display for BigValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // groups of 9 decimal digits, least significant first
+        let mut groups = vec![];
+        let mut limbs = self.limbs.clone();
+
+        while !limbs.is_empty() {
+            let mut remainder = 0;
+
+            for limb in limbs.iter_mut().rev() {
+                let n = (remainder << 32) | u64::from(*limb);
+
+                *limb = (n / 1_000_000_000) as u32;
+                remainder = n % 1_000_000_000;
+            }
+
+            groups.push(remainder);
+            limbs = BigValue::normalized(limbs).limbs;
+        }
+
+        match groups.split_last() {
+            None => write!(f, "0"),
+            Some((first, rest)) => {
+                write!(f, "{}", first)?;
+
+                rest.iter().rev().try_for_each(|group| write!(f, "{:09}", group))
+            },
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Overflow {
+    // the indexes of the sub-packets leading to the one that overflowed
+    pub path: Vec<usize>,
+    pub packet: Packet,
+}
+
+impl std::fmt::Display for Overflow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.path.is_empty() {
+            true => write!(f, "overflow in the outermost packet: {}", self.packet.sexpr()),
+            false => write!(f, "overflow in sub-packet {:?}: {}", self.path, self.packet.sexpr()),
+        }
+    }
+}
+
+impl std::error::Error for Overflow {}
+
+// how an operator packet gives the size of its sub-packets
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum LengthType {
+    // type ID 0: total length of the sub-packets in bits, on 15 bits
+    Bits,
+    // type ID 1: number of sub-packets, on 11 bits
+    Count,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum EncodeError {
+    // versions are 3 bits long
+    Version(usize),
+    // an operator whose sub-packets don't fit in its length type
+    TooLarge { sub_packets: usize, bits: usize },
+}
+
+impl std::fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EncodeError::Version(version) => write!(f, "can't encode version {}", version),
+            EncodeError::TooLarge { sub_packets, bits } => write!(
+                f, "can't encode the length of {} sub-packets taking {} bits", sub_packets, bits,
+            ),
+        }
+    }
+}
+
+impl std::error::Error for EncodeError {}
+
+fn push_bits(bits: &mut Vec<bool>, n: usize, length: usize) {
+    bits.extend((0..length).rev().map(|shift| (n >> shift) & 1 == 1));
+}
+
+impl Packet {
+    // operators use the number of sub-packets when it fits, it takes fewer bits
+    pub fn to_bits(&self) -> Result<Vec<bool>, EncodeError> {
+        let mut bits = vec![];
+
+        self.write(&mut bits, None)?;
+
+        Ok(bits)
+    }
+
+    // all the operators use `length_type`
+    pub fn to_bits_with(&self, length_type: LengthType) -> Result<Vec<bool>, EncodeError> {
+        let mut bits = vec![];
+
+        self.write(&mut bits, Some(length_type))?;
+
+        Ok(bits)
+    }
+
+    // padded with zeros up to a whole number of bytes, like the transmissions
+    pub fn to_hex(&self) -> Result<String, EncodeError> {
+        Ok(to_hex(&self.to_bits()?))
+    }
+
+    fn write(&self, bits: &mut Vec<bool>, length_type: Option<LengthType>) -> Result<(), EncodeError> {
+        match *self {
+            Packet::Literal { version, value } => {
+                if version > 7 {
+                    return Err(EncodeError::Version(version));
+                }
+
+                push_bits(bits, version, 3);
+                push_bits(bits, 4, 3);
+
+                // groups of 4 bits, each one prefixed by 1 except the last one
+                let groups = (usize::BITS - value.leading_zeros()).div_ceil(4).max(1) as usize;
+
+                for group in (0..groups).rev() {
+                    bits.push(group > 0);
+                    push_bits(bits, value >> (group * 4), 4);
+                }
+            },
+            Packet::Operator { version, operator, ref packets } => {
+                if version > 7 {
+                    return Err(EncodeError::Version(version));
+                }
+
+                let mut sub_packets = vec![];
+
+                for packet in packets {
+                    packet.write(&mut sub_packets, length_type)?;
+                }
+
+                push_bits(bits, version, 3);
+                push_bits(bits, operator.type_id(), 3);
+
+                match length_type {
+                    Some(LengthType::Count) | None if packets.len() < 1 << 11 => {
+                        bits.push(true);
+                        push_bits(bits, packets.len(), 11);
+                    },
+                    Some(LengthType::Bits) | None if sub_packets.len() < 1 << 15 => {
+                        bits.push(false);
+                        push_bits(bits, sub_packets.len(), 15);
+                    },
+                    _ => return Err(EncodeError::TooLarge { sub_packets: packets.len(), bits: sub_packets.len() }),
+                }
+
+                bits.extend(sub_packets);
+            },
+        }
+
+        Ok(())
+    }
+}
+
+pub fn to_hex(bits: &[bool]) -> String {
+    let mut bits = bits.to_vec();
+
+    bits.resize(bits.len().div_ceil(8) * 8, false);
+
+    bits.chunks(4)
+        .map(|nibble| std::char::from_digit(to_usize(nibble) as u32, 16).unwrap().to_ascii_uppercase())
+        .collect()
+}
+
+// decoding, evaluating and printing packets all go down the sub-packets recursively,
+// this keeps the stack from overflowing on transmissions made of nested operators
+pub const MAX_DEPTH: usize = 256;
+
+// `depth` is the one of the operator the sub-packets are in, the outermost packet is at 1
+fn read_packets<R: Read>(reader: &mut BitReader<R>, depth: usize) -> Result<Vec<Packet>, BitsError> {
     let mut packets = vec![];
 
-    let mut total_length = usize::MAX;
-    let mut number_of_subpacket = usize::MAX;
-    let mut pos = 0;
+    if read(reader, 1, "a length type ID")? == 1 {
+        for _ in 0..read(reader, 11, "a number of sub-packets")? {
+            packets.push(read_packet(reader, depth + 1)?);
+        }
 
-    if bits[0] {
-        number_of_subpacket = to_usize(&bits[1..12]);
-        pos += 12;
-    } else {
-        pos += 16;
-        total_length = pos + to_usize(&bits[1..16]);
+        return Ok(packets);
     }
 
-    while pos < total_length && packets.len() < number_of_subpacket {
-        let (packet, n) = read_packet(&bits[pos..]);
+    let length = read(reader, 15, "a length of sub-packets")?;
+    let end = reader.position() + length;
 
-        pos += n;
-        packets.push(packet);
+    if end > reader.limit() {
+        return Err(BitsError { offset: reader.position(), expected: format!("{} bits of sub-packets", length) });
     }
 
-    (packets, pos)
+    // sub-packets can't go past their length
+    let limit = reader.set_limit(end);
+
+    while reader.position() < end {
+        packets.push(read_packet(reader, depth + 1)?);
+    }
+
+    reader.set_limit(limit);
+
+    Ok(packets)
 }
 
-fn read_packet(bits: &[bool]) -> (Packet, usize) {
-    let version = to_usize(&bits[0..3]);
+fn read_packet<R: Read>(reader: &mut BitReader<R>, depth: usize) -> Result<Packet, BitsError> {
+    let pos = reader.position();
 
-    match to_usize(&bits[3..6]) {
-        4 => {
-            let (value, n) = read_literal_value(&bits[6..]);
-            (Packet::Literal {
-                version,
-                value,
-            }, 6 + n)
-        },
+    if depth > MAX_DEPTH {
+        return Err(BitsError { offset: pos, expected: format!("at most {} nested packets", MAX_DEPTH) });
+    }
+    let version = read(reader, 3, "a packet version")?;
+
+    match read(reader, 3, "a packet type ID")? {
+        4 => Ok(Packet::Literal {
+            version,
+            value: read_literal_value(reader)?,
+        }),
         type_id => {
-            let (packets, n) = read_packets(&bits[6..]);
-            (Packet::Operator {
+            let operator = Operator::from_type_id(type_id)
+                .ok_or_else(|| BitsError { offset: pos + 3, expected: "an operator type ID".to_string() })?;
+            let packets = read_packets(reader, depth)?;
+
+            if !operator.accepts(packets.len()) {
+                let expected = match operator {
+                    Operator::GreaterThan | Operator::LessThan | Operator::EqualTo => "exactly 2 sub-packets",
+                    _ => "at least 1 sub-packet",
+                };
+
+                return Err(BitsError {
+                    offset: pos,
+                    expected: format!("{} for `{}`, found {}", expected, operator.symbol(), packets.len()),
+                });
+            }
+
+            Ok(Packet::Operator {
                 version,
-                type_id,
+                operator,
                 packets,
-            }, 6 + n)
+            })
         },
     }
 }
 
-impl From<Input> for Packet {
-    fn from(input: Input) -> Self {
-        read_packet(&input.bits).0
+pub fn decode(bits: &[bool]) -> Result<Packet, BitsError> {
+    let bytes = bits.chunks(8)
+        .map(|byte| (to_usize(byte) << (8 - byte.len())) as u8)
+        .collect::<Vec<_>>();
+
+    decode_from(BitReader::new(&bytes[..]).with_limit(bits.len()))
+}
+
+// the transmission is one packet, followed by zeros up to the end of the last hexadecimal digit
+pub fn decode_from<R: Read>(mut reader: BitReader<R>) -> Result<Packet, BitsError> {
+    let packet = read_packet(&mut reader, 1)?;
+
+    loop {
+        let offset = reader.position();
+
+        match reader.read_bits(1) {
+            Ok(0) => {},
+            Ok(_) => return Err(BitsError { offset, expected: "only zeros after the packet".to_string() }),
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(packet),
+            Err(e) if e.kind() == io::ErrorKind::InvalidData => return Err(BitsError { offset, expected: e.to_string() }),
+            Err(e) => return Err(BitsError { offset, expected: format!("only zeros after the packet, reading failed: {}", e) }),
+        }
+    }
+}
+
+impl Input {
+    pub fn reader(&self) -> BitReader<&[u8]> {
+        BitReader::new(&self.bytes[..]).with_limit(self.length)
+    }
+
+    pub fn packet(&self) -> Result<Packet, BitsError> {
+        decode_from(self.reader())
+    }
+}
+
+impl TryFrom<Input> for Packet {
+    type Error = BitsError;
+
+    fn try_from(input: Input) -> Result<Self, Self::Error> {
+        input.packet()
     }
 }
 
 #[allow(unused_variables)]
 #[aoc_generator(day16)]
-fn input_generator(input: &str) -> Input {
-    Input {
-        bits: input.chars()
-            .flat_map(|c| match c {
-                '0' => [false, false, false, false],
-                '1' => [false, false, false, true],
-                '2' => [false, false, true,  false],
-                '3' => [false, false, true,  true],
-                '4' => [false, true,  false, false],
-                '5' => [false, true,  false, true],
-                '6' => [false, true,  true,  false],
-                '7' => [false, true,  true,  true],
-                '8' => [true,  false, false, false],
-                '9' => [true,  false, false, true],
-                'A' => [true,  false, true,  false],
-                'B' => [true,  false, true,  true],
-                'C' => [true,  true,  false, false],
-                'D' => [true,  true,  false,  true],
-                'E' => [true,  true,  true,  false],
-                'F' => [true,  true,  true, true],
-                _ => panic!(),
-            })
-            .collect::<Vec<_>>()
+fn input_generator(input: &str) -> Result<Input, ParseError> {
+    let digits = input.trim_end().chars()
+        .enumerate()
+        .map(|(column, c)| c.to_digit(16)
+            .map(|digit| digit as u8)
+            .ok_or_else(|| ParseError::new(16, 1, column + 1, "an hexadecimal digit")))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let input = Input {
+        // an odd number of digits leaves the last half byte unused
+        bytes: digits.chunks(2).map(|pair| (pair[0] << 4) | pair.get(1).unwrap_or(&0)).collect(),
+        length: digits.len() * 4,
+    };
+
+    match input.packet() {
+        // errors point at the hexadecimal digit with the faulty bit
+        Err(e) => Err(ParseError::new(16, 1, e.offset / 4 + 1, format!("{} at bit {}", e.expected, e.offset))),
+        Ok(_) => Ok(input),
     }
 }
 
@@ -169,14 +748,44 @@ type Output = usize;
 #[aoc(day16, part1)]
 
 fn part1(input: &Input) -> Output {
-    Packet::from(input.clone()).sum_of_versions()
+    input.packet().expect("a packet checked by the parser").sum_of_versions()
 }
 
 #[allow(unused_variables)]
 #[aoc(day16, part2)]
 
-fn part2(input: &Input) -> Output {
-    Packet::from(input.clone()).eval()
+fn part2(input: &Input) -> Answer {
+    let packet = input.packet().expect("a packet checked by the parser");
+
+    // exact, even past the numbers answers can hold
+    match packet.eval().ok().and_then(|value| i64::try_from(value).ok()) {
+        Some(value) => Answer::Number(value),
+        None => Answer::Text(packet.eval_big().to_string().into()),
+    }
+}
+
+pub struct Day16;
+
+impl Puzzle for Day16 {
+    const DAY: u8 = 16;
+    const EXAMPLE: &'static str = "9C0141080250320F1802104A08";
+    const EXAMPLE_ANSWERS: [Option<Answer>; 2] = [None, Some(Answer::Number(1))];
+
+    type Input = Input;
+    type Output1 = Output;
+    type Output2 = Answer;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
 }
 
 #[allow(unreachable_code)]
@@ -186,29 +795,29 @@ mod tests {
 
     #[test]
     fn part2_provided_exemple() {
-        assert_eq!(part2(&input_generator("C200B40A82")), 3);
-        assert_eq!(part2(&input_generator("04005AC33890")), 54);
-        assert_eq!(part2(&input_generator("880086C3E88112")), 7);
-        assert_eq!(part2(&input_generator("CE00C43D881120")), 9);
-        assert_eq!(part2(&input_generator("D8005AC2A8F0")), 1);
-        assert_eq!(part2(&input_generator("F600BC2D8F")), 0);
-        assert_eq!(part2(&input_generator("9C005AC2F8F0")), 0);
-        assert_eq!(part2(&input_generator("9C0141080250320F1802104A08")), 1);
+        assert_eq!(part2(&input_generator("C200B40A82").unwrap()), Answer::Number(3));
+        assert_eq!(part2(&input_generator("04005AC33890").unwrap()), Answer::Number(54));
+        assert_eq!(part2(&input_generator("880086C3E88112").unwrap()), Answer::Number(7));
+        assert_eq!(part2(&input_generator("CE00C43D881120").unwrap()), Answer::Number(9));
+        assert_eq!(part2(&input_generator("D8005AC2A8F0").unwrap()), Answer::Number(1));
+        assert_eq!(part2(&input_generator("F600BC2D8F").unwrap()), Answer::Number(0));
+        assert_eq!(part2(&input_generator("9C005AC2F8F0").unwrap()), Answer::Number(0));
+        assert_eq!(part2(&input_generator("9C0141080250320F1802104A08").unwrap()), Answer::Number(1));
     }
 
     #[test]
     fn part1_provided_exemple() {
-        assert_eq!(part1(&input_generator("8A004A801A8002F478")), 16);
-        assert_eq!(part1(&input_generator("620080001611562C8802118E34")), 12);
-        assert_eq!(part1(&input_generator("C0015000016115A2E0802F182340")), 23);
-        assert_eq!(part1(&input_generator("A0016C880162017C3686B18A3D4780")), 31);
+        assert_eq!(part1(&input_generator("8A004A801A8002F478").unwrap()), 16);
+        assert_eq!(part1(&input_generator("620080001611562C8802118E34").unwrap()), 12);
+        assert_eq!(part1(&input_generator("C0015000016115A2E0802F182340").unwrap()), 23);
+        assert_eq!(part1(&input_generator("A0016C880162017C3686B18A3D4780").unwrap()), 31);
     }
 
     #[test]
     fn operator_length_type_1_packet() {
-        assert_eq!(Packet::from(input_generator("EE00D40C823060")), Packet::Operator {
+        assert_eq!(Packet::try_from(input_generator("EE00D40C823060").unwrap()), Ok(Packet::Operator {
             version: 7,
-            type_id: 3,
+            operator: Operator::Maximum,
             packets: vec![
                 Packet::Literal {
                     version: 2,
@@ -223,14 +832,14 @@ mod tests {
                     value: 3,
                 },
             ],
-        });
+        }));
     }
 
     #[test]
     fn operator_length_type_0_packet() {
-        assert_eq!(Packet::from(input_generator("38006F45291200")), Packet::Operator {
+        assert_eq!(Packet::try_from(input_generator("38006F45291200").unwrap()), Ok(Packet::Operator {
             version: 1,
-            type_id: 6,
+            operator: Operator::LessThan,
             packets: vec![
                 Packet::Literal {
                     version: 6,
@@ -241,46 +850,354 @@ mod tests {
                     value: 20,
                 },
             ],
-        });
+        }));
     }
 
     #[test]
     fn literal_packet() {
-        assert_eq!(Packet::from(input_generator("D2FE28")), Packet::Literal {
+        assert_eq!(Packet::try_from(input_generator("D2FE28").unwrap()), Ok(Packet::Literal {
             version: 6,
             value: 2021,
-        });
+        }));
     }
 
     #[test]
-    fn part1_provided_exemple_input() {
-        assert_eq!(input_generator("D2FE28"), Input {
-            bits: vec![
-                true,
-                true,
-                false,
-                true,
-                false,
-                false,
-                true,
-                false,
-                true,
-                true,
-                true,
-                true,
-                true,
-                true,
-                true,
-                false,
-                false,
-                false,
-                true,
-                false,
-                true,
-                false,
-                false,
-                false,
+    fn overflow() {
+        let literal = |value| Packet::Literal { version: 0, value };
+        let operator = |operator, packets| Packet::Operator { version: 1, operator, packets };
+
+        let sum = operator(Operator::Sum, vec![literal(usize::MAX), literal(1)]);
+
+        assert_eq!(sum.eval(), Err(Overflow { path: vec![], packet: sum.clone() }));
+        assert_eq!(sum.eval_big().to_string(), "18446744073709551616");
+        assert_eq!(sum.eval_big().to_usize(), None);
+        assert_eq!(
+            sum.eval().unwrap_err().to_string(),
+            "overflow in the outermost packet: (+@1 18446744073709551615@0 1@0)",
+        );
+
+        // the comparison doesn't overflow, the product of its second sub-packet does
+        let product = operator(Operator::Product, vec![literal(1 << 32); 3]);
+        let packet = operator(Operator::Sum, vec![
+            literal(1),
+            operator(Operator::LessThan, vec![literal(2), product.clone()]),
+        ]);
+
+        assert_eq!(packet.eval(), Err(Overflow { path: vec![1, 1], packet: product.clone() }));
+        assert!(packet.eval().unwrap_err().to_string().starts_with("overflow in sub-packet [1, 1]: (*@1 4294967296@0"));
+        assert_eq!(packet.eval_big().to_usize(), Some(2));
+        assert_eq!(product.eval_big().to_string(), "79228162514264337593543950336");
+
+        // the example transmissions fit either way
+        for (transmission, value) in [("C200B40A82", 3), ("04005AC33890", 54), ("CE00C43D881120", 9), ("9C0141080250320F1802104A08", 1)] {
+            let packet = Packet::try_from(input_generator(transmission).unwrap()).unwrap();
+
+            assert_eq!(packet.eval(), Ok(value));
+            assert_eq!(packet.eval_big(), BigValue::from(value));
+        }
+    }
+
+    #[test]
+    fn large_answers() {
+        let literal = |value| Packet::Literal { version: 0, value };
+        let product = |packets| Packet::Operator { version: 0, operator: Operator::Product, packets };
+
+        let hex = product(vec![literal(1 << 40); 3]).to_hex().unwrap();
+
+        assert_eq!(crate::solve(16, 2, &hex), Ok(Answer::Text("1329227995784915872903807060280344576".into())));
+
+        // too large for the numbers of answers but not for a usize
+        let hex = product(vec![literal(1 << 40), literal(1 << 23)]).to_hex().unwrap();
+
+        assert_eq!(crate::solve(16, 2, &hex), Ok(Answer::Text("9223372036854775808".into())));
+
+        let hex = product(vec![literal(1 << 40), literal((1 << 23) - 1)]).to_hex().unwrap();
+
+        assert_eq!(crate::solve(16, 2, &hex), Ok(Answer::Number(i64::MAX - (1 << 40) + 1)));
+
+        // a literal of 17 groups, the exact mode can't help with it
+        assert_eq!(
+            crate::solve(16, 2, "D3FFFFFFFFFFFFFFFFFFFDE0").unwrap_err().to_string(),
+            "day 16 input, line 1, column 22: expected a literal value of at most 64 bits at bit 86",
+        );
+    }
+
+    #[test]
+    fn big_values() {
+        let big = |value: usize| BigValue::from(value);
+
+        assert_eq!(big(0).to_string(), "0");
+        assert_eq!(big(1_000_000_000).to_string(), "1000000000");
+        assert_eq!(big(usize::MAX).to_usize(), Some(usize::MAX));
+        assert_eq!(big(0).try_mul(&big(usize::MAX)), Some(big(0)));
+        assert!(big(1 << 32) > big(u32::MAX as usize));
+        assert!(big(usize::MAX).try_add(&big(1)).unwrap() > big(usize::MAX));
+
+        // 10^40 = (10^10)^4
+        let power = (0..4).fold(big(1), |power, _| power.try_mul(&big(10_000_000_000)).unwrap());
+
+        assert_eq!(power.to_string(), format!("1{}", "0".repeat(40)));
+        assert_eq!(power.try_add(&big(7)).unwrap().to_string(), format!("1{}7", "0".repeat(39)));
+    }
+
+    #[test]
+    fn encode() {
+        let literal = |version, value| Packet::Literal { version, value };
+
+        assert_eq!(literal(6, 2021).to_hex(), Ok("D2FE28".to_string()));
+        assert_eq!(literal(0, 0).to_bits(), Ok(vec![false, false, false, true, false, false, false, false, false, false, false]));
+
+        let operator = Packet::Operator { version: 1, operator: Operator::LessThan, packets: vec![literal(6, 10), literal(2, 20)] };
+
+        assert_eq!(operator.to_bits_with(LengthType::Bits).map(|bits| to_hex(&bits)), Ok("38006F45291200".to_string()));
+
+        let operator = Packet::Operator { version: 7, operator: Operator::Maximum, packets: vec![literal(2, 1), literal(4, 2), literal(1, 3)] };
+
+        assert_eq!(operator.to_hex(), Ok("EE00D40C823060".to_string()));
+        assert_eq!(operator.to_bits_with(LengthType::Count).map(|bits| to_hex(&bits)), operator.to_hex());
+    }
+
+    #[test]
+    fn encode_errors() {
+        assert_eq!(Packet::Literal { version: 8, value: 1 }.to_bits(), Err(EncodeError::Version(8)));
+        assert_eq!(
+            Packet::Operator { version: 9, operator: Operator::Sum, packets: vec![] }.to_bits().unwrap_err().to_string(),
+            "can't encode version 9",
+        );
+
+        // too many sub-packets to count them, but few enough bits
+        let operator = Packet::Operator {
+            version: 0,
+            operator: Operator::Sum,
+            packets: vec![Packet::Literal { version: 0, value: 1 }; 2048],
+        };
+
+        assert_eq!(operator.to_bits().map(|bits| bits[6]), Ok(false));
+        assert_eq!(
+            operator.to_bits_with(LengthType::Count).unwrap_err().to_string(),
+            "can't encode the length of 2048 sub-packets taking 22528 bits",
+        );
+        assert_eq!(decode(&operator.to_bits().unwrap()), Ok(operator));
+    }
+
+    #[test]
+    fn round_trip() {
+        let transmissions = [
+            "D2FE28", "38006F45291200", "EE00D40C823060", "8A004A801A8002F478", "620080001611562C8802118E34",
+            "C0015000016115A2E0802F182340", "A0016C880162017C3686B18A3D4780", "9C0141080250320F1802104A08",
+        ];
+
+        for transmission in transmissions {
+            let packet = Packet::try_from(input_generator(transmission).unwrap()).unwrap();
+
+            for bits in [packet.to_bits(), packet.to_bits_with(LengthType::Bits), packet.to_bits_with(LengthType::Count)] {
+                assert_eq!(decode(&bits.unwrap()), Ok(packet.clone()));
+            }
+
+            assert_eq!(Packet::try_from(input_generator(&packet.to_hex().unwrap()).unwrap()), Ok(packet));
+        }
+
+        let packet = Packet::Operator {
+            version: 5,
+            operator: Operator::Product,
+            packets: vec![
+                Packet::Literal { version: 7, value: usize::MAX },
+                Packet::Operator { version: 0, operator: Operator::EqualTo, packets: vec![Packet::Literal { version: 1, value: 16 }; 2] },
             ],
+        };
+
+        for bits in [packet.to_bits(), packet.to_bits_with(LengthType::Bits), packet.to_bits_with(LengthType::Count)] {
+            assert_eq!(decode(&bits.unwrap()), Ok(packet.clone()));
+        }
+    }
+
+    #[test]
+    fn operators() {
+        for type_id in 0..8 {
+            assert_eq!(Operator::from_type_id(type_id).map(Operator::type_id), if type_id == 4 { None } else { Some(type_id) });
+        }
+
+        assert_eq!(Operator::from_type_id(8), None);
+
+        let literal = |version, value| Packet::Literal { version, value };
+        let decode_operator = |operator, packets| decode(&Packet::Operator { version: 3, operator, packets }.to_bits().unwrap());
+        let error = |expected: &str| Err(BitsError { offset: 0, expected: expected.to_string() });
+
+        assert_eq!(decode_operator(Operator::GreaterThan, vec![literal(1, 1)]), error("exactly 2 sub-packets for `>`, found 1"));
+        assert_eq!(decode_operator(Operator::EqualTo, vec![literal(1, 1); 3]), error("exactly 2 sub-packets for `==`, found 3"));
+        assert_eq!(decode_operator(Operator::Minimum, vec![]), error("at least 1 sub-packet for `min`, found 0"));
+        assert_eq!(decode_operator(Operator::Sum, vec![literal(1, 1)]).unwrap().eval(), Ok(1));
+
+        // the offset of the faulty sub-packet
+        let packet = Packet::Operator {
+            version: 0,
+            operator: Operator::Sum,
+            packets: vec![literal(0, 1), Packet::Operator { version: 0, operator: Operator::LessThan, packets: vec![] }],
+        };
+        let hex = packet.to_hex().unwrap();
+
+        assert_eq!(decode(&packet.to_bits().unwrap()), Err(BitsError { offset: 29, expected: "exactly 2 sub-packets for `<`, found 0".to_string() }));
+        assert_eq!(input_generator(&hex), Err(ParseError::new(16, 1, 8, "exactly 2 sub-packets for `<`, found 0 at bit 29")));
+    }
+
+    #[test]
+    fn printers() {
+        let packet = |hex| Packet::try_from(input_generator(hex).unwrap()).unwrap();
+
+        assert_eq!(packet("D2FE28").infix(), "2021@6");
+        assert_eq!(packet("D2FE28").sexpr(), "2021@6");
+        assert_eq!(packet("38006F45291200").infix(), "(10@6 < 20@2)@1");
+        assert_eq!(packet("38006F45291200").sexpr(), "(<@1 10@6 20@2)");
+        assert_eq!(packet("EE00D40C823060").infix(), "max(1@2, 2@4, 3@1)@7");
+        assert_eq!(packet("EE00D40C823060").sexpr(), "(max@7 1@2 2@4 3@1)");
+        assert_eq!(packet("9C0141080250320F1802104A08").infix(), "((1@2 + 3@4)@2 == (2@0 * 2@2)@6)@4");
+        assert_eq!(packet("9C0141080250320F1802104A08").sexpr(), "(==@4 (+@2 1@2 3@4) (*@6 2@0 2@2))");
+    }
+
+    #[test]
+    fn part1_provided_exemple_input() {
+        assert_eq!(input_generator("D2FE28").unwrap(), Input {
+            bytes: vec![0xD2, 0xFE, 0x28],
+            length: 24,
         });
     }
+
+    #[test]
+    fn input_errors() {
+        assert_eq!(input_generator("D2FE28\n").map(|input| input.length), Ok(24));
+        assert_eq!(input_generator("d2fe28").map(|input| input.length), Ok(24));
+        assert_eq!(input_generator("D2FG28"), Err(ParseError::new(16, 1, 4, "an hexadecimal digit")));
+        assert_eq!(input_generator("D2FE"), Err(ParseError::new(16, 1, 5, "a literal value group (5 bits) at bit 16")));
+        assert_eq!(input_generator("D2FE29"), Err(ParseError::new(16, 1, 6, "only zeros after the packet at bit 23")));
+    }
+
+    #[test]
+    fn bit_reader() {
+        let mut reader = BitReader::new(&[0b1011_0011, 0b1000_0001, 0xFF][..]);
+
+        assert_eq!(reader.read_bits(3).unwrap(), 0b101);
+        assert_eq!(reader.read_bits(0).unwrap(), 0);
+        assert_eq!(reader.read_bits(9).unwrap(), 0b1_0011_1000);
+        assert_eq!(reader.position(), 12);
+        assert_eq!(reader.read_bits(12).unwrap(), 0b0001_1111_1111);
+        assert_eq!(reader.read_bits(1).unwrap_err().kind(), io::ErrorKind::UnexpectedEof);
+
+        let mut reader = BitReader::new(&[0xFF; 9][..]).with_limit(70);
+
+        assert_eq!(reader.read_bits(64).unwrap(), usize::MAX);
+        assert_eq!(reader.read_bits(7).unwrap_err().kind(), io::ErrorKind::UnexpectedEof);
+        assert_eq!(reader.read_bits(6).unwrap(), 0b111111);
+
+        let mut reader = BitReader::hex("d2F\nE28".as_bytes());
+
+        assert_eq!(reader.read_bits(12).unwrap(), 0xD2F);
+        assert_eq!(reader.read_bits(1).unwrap_err().kind(), io::ErrorKind::UnexpectedEof);
+
+        let mut reader = BitReader::hex("D2G".as_bytes());
+
+        assert_eq!(reader.read_bits(12).unwrap_err().kind(), io::ErrorKind::InvalidData);
+        assert_eq!(reader.position(), 8);
+    }
+
+    #[test]
+    fn decode_from_reader() {
+        for transmission in ["D2FE28", "38006F45291200", "9C0141080250320F1802104A08"] {
+            let input = input_generator(transmission).unwrap();
+
+            assert_eq!(decode_from(BitReader::hex(transmission.as_bytes())), input.packet());
+            assert_eq!(decode_from(BitReader::hex(format!("{}\n", transmission).as_bytes())), input.packet());
+            assert_eq!(decode_from(BitReader::new(&input.bytes[..])), input.packet());
+        }
+
+        assert_eq!(
+            decode_from(BitReader::hex("D2FE2X".as_bytes())),
+            Err(BitsError { offset: 20, expected: "an hexadecimal digit".to_string() }),
+        );
+        assert_eq!(
+            decode_from(BitReader::hex("38006F45X91200".as_bytes())),
+            Err(BitsError { offset: 32, expected: "an hexadecimal digit".to_string() }),
+        );
+
+        // a sum of 256 sums of 2047 literals
+        let literals = Packet::Operator {
+            version: 1,
+            operator: Operator::Sum,
+            packets: vec![Packet::Literal { version: 2, value: 3 }; 2047],
+        };
+        let packet = Packet::Operator { version: 0, operator: Operator::Sum, packets: vec![literals; 256] };
+        let hex = packet.to_hex().unwrap();
+
+        assert_eq!(hex.len(), 1_442_246);
+        assert_eq!(decode_from(BitReader::hex(io::BufReader::new(hex.as_bytes()))).unwrap().eval(), Ok(3 * 2047 * 256));
+    }
+
+    #[test]
+    fn nesting() {
+        // sums of one sub-packet each, counted in sub-packets, around a literal
+        let nested = |depth: usize| {
+            let mut bits = vec![];
+
+            for _ in 1..depth {
+                // version 0, type ID 0, length type ID 1
+                push_bits(&mut bits, 1, 7);
+                push_bits(&mut bits, 1, 11);
+            }
+
+            // version 0, type ID 4, a 7
+            push_bits(&mut bits, 4, 6);
+            push_bits(&mut bits, 7, 5);
+
+            bits
+        };
+
+        assert_eq!(decode(&nested(MAX_DEPTH)).unwrap().eval(), Ok(7));
+        assert_eq!(
+            decode(&nested(MAX_DEPTH + 1)),
+            Err(BitsError { offset: MAX_DEPTH * 18, expected: "at most 256 nested packets".to_string() }),
+        );
+
+        // about 450 KB of hexadecimal digits
+        let hex = to_hex(&nested(100_000));
+
+        assert_eq!(
+            decode_from(BitReader::hex(hex.as_bytes())),
+            Err(BitsError { offset: MAX_DEPTH * 18, expected: "at most 256 nested packets".to_string() }),
+        );
+        assert_eq!(
+            crate::solve(16, 2, &hex).unwrap_err().to_string(),
+            "day 16 input, line 1, column 1153: expected at most 256 nested packets at bit 4608",
+        );
+    }
+
+    #[test]
+    fn decode_errors() {
+        // without the checks of the parser
+        let bits = |hex: &str| hex.chars()
+            .flat_map(|c| {
+                let n = c.to_digit(16).unwrap();
+
+                (0..4).rev().map(move |shift| (n >> shift) & 1 == 1)
+            })
+            .collect::<Vec<_>>();
+
+        let error = |offset, expected: &str| Err(BitsError { offset, expected: expected.to_string() });
+
+        assert_eq!(decode(&[]), error(0, "a packet version (3 bits)"));
+        assert_eq!(decode(&bits("D")), error(3, "a packet type ID (3 bits)"));
+        assert_eq!(decode(&bits("D2FE")), error(16, "a literal value group (5 bits)"));
+        assert_eq!(decode(&bits("D2FE29")), error(23, "only zeros after the packet"));
+        assert_eq!(decode(&bits("38")[..6]), error(6, "a length type ID (1 bits)"));
+        assert_eq!(decode(&bits("3800")), error(7, "a length of sub-packets (15 bits)"));
+        assert_eq!(decode(&bits("38006F4529")), error(22, "27 bits of sub-packets"));
+        assert_eq!(decode(&bits("EE0")), error(7, "a number of sub-packets (11 bits)"));
+        // the third literal is missing
+        assert_eq!(decode(&bits("EE00D40C82")), error(40, "a packet version (3 bits)"));
+        // the length of the sub-packets only covers the first literal (11 bits instead of 27)
+        assert_eq!(decode(&bits("38002F45291200")), error(0, "exactly 2 sub-packets for `<`, found 1"));
+        // or stops in the middle of the second one (20 bits)
+        assert_eq!(decode(&bits("38005345291200")), error(39, "a literal value group (5 bits)"));
+        assert_eq!(decode(&bits("D3FFFFFFFFFFFFFFFFFFFDE0")), error(86, "a literal value of at most 64 bits"));
+
+        assert_eq!(error(3, "a packet type ID (3 bits)").unwrap_err().to_string(), "bit 3: expected a packet type ID (3 bits)");
+    }
 }
@@ -1,8 +1,10 @@
-use std::{ops::RangeInclusive, convert::Infallible};
+use std::ops::RangeInclusive;
+
+use crate::{parse::{self, ParseError}, puzzle::Puzzle, solve::Answer};
 
 #[derive(Debug, PartialEq, parse_display::FromStr)]
 #[display("target area: {target_area}")]
-struct Input {
+pub struct Input {
     target_area: Area
 }
 
@@ -19,30 +21,29 @@ struct AxisRange {
 }
 
 impl std::str::FromStr for AxisRange {
-    type Err = Infallible;
+    type Err = parse_display::ParseError;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        Ok(input.split_once("..")
-            .map(|(start, end)| AxisRange {
-                range: RangeInclusive::new(
-                    start.parse().unwrap(),
-                    end.parse().unwrap(),
-                ),
-            })
-            .unwrap(),
-        )
+        match input.split_once("..").map(|(start, end)| (start.parse(), end.parse())) {
+            Some((Ok(start), Ok(end))) => Ok(AxisRange {
+                range: RangeInclusive::new(start, end),
+            }),
+            _ => Err(parse_display::ParseError::new()),
+        }
     }
 }
 
 #[allow(unused_variables)]
 #[aoc_generator(day17)]
-fn input_generator(input: &str) -> Input {
-    input.parse::<Input>().unwrap()
+fn input_generator(input: &str) -> Result<Input, ParseError> {
+    let line = (1, input.trim_end());
+
+    parse::parse(17, line, line.1, "target area: x=A..B, y=C..D")
 }
 
 #[test]
 fn part1_provided_exemple_input() {
-    assert_eq!(input_generator(exemple_raw_input()), Input {
+    assert_eq!(input_generator(Day17::EXAMPLE).unwrap(), Input {
         target_area: Area {
             x_range: AxisRange {
                 range: 20..=30,
@@ -54,6 +55,14 @@ fn part1_provided_exemple_input() {
     });
 }
 
+#[test]
+fn input_errors() {
+    assert_eq!(
+        input_generator("target area: x=20..30, y=-10"),
+        Err(ParseError::new(17, 1, 1, "target area: x=A..B, y=C..D")),
+    );
+}
+
 type Output = i32;
 
 #[allow(unused_variables)]
@@ -90,7 +99,7 @@ fn part1(input: &Input) -> Output {
 
 #[test]
 fn part1_provided_exemple() {
-    assert_eq!(part1(&input_generator(exemple_raw_input())), 45);
+    assert_eq!(part1(&input_generator(Day17::EXAMPLE).unwrap()), 45);
 }
 
 #[allow(unreachable_code)]
@@ -167,10 +176,29 @@ fn part2(input: &Input) -> Output {
 #[allow(unreachable_code)]
 #[test]
 fn part2_provided_exemple() {
-    assert_eq!(part2(&input_generator(exemple_raw_input())), 112);
+    assert_eq!(part2(&input_generator(Day17::EXAMPLE).unwrap()), 112);
 }
 
-#[allow(dead_code)]
-fn exemple_raw_input() -> &'static str {
-    "target area: x=20..30, y=-10..-5"
+pub struct Day17;
+
+impl Puzzle for Day17 {
+    const DAY: u8 = 17;
+    const EXAMPLE: &'static str = "target area: x=20..30, y=-10..-5";
+    const EXAMPLE_ANSWERS: [Option<Answer>; 2] = [Some(Answer::Number(45)), Some(Answer::Number(112))];
+
+    type Input = Input;
+    type Output1 = Output;
+    type Output2 = Output;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
 }
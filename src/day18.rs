@@ -1,64 +1,197 @@
+use std::{fmt::Display, iter::Sum, ops::Add, str::FromStr};
+
+use crate::{parse::{self, ParseError}, puzzle::Puzzle, solve::Answer};
+
 #[derive(Debug, PartialEq)]
-struct Input {
-    numbers: Vec<Vec<(u8, u8)>>
+pub struct Input {
+    numbers: Vec<SnailfishNumber>,
+}
+
+// the regular numbers from left to right, with their depth in the pairs.
+// values fit in a byte when parsed but explodes add them together
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SnailfishNumber {
+    values: Vec<(u32, u8)>,
+}
+
+// position of the error in the line, and what was expected there
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct FishError {
+    pub column: usize,
+    pub expected: &'static str,
+}
+
+impl Display for FishError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "column {}: expected {}", self.column, self.expected)
+    }
+}
+
+impl std::error::Error for FishError {}
+
+fn expect(s: &[u8], pos: usize, expected: u8, description: &'static str) -> Result<(), FishError> {
+    match s.get(pos) {
+        Some(&c) if c == expected => Ok(()),
+        _ => Err(FishError { column: pos + 1, expected: description }),
+    }
+}
+
+// the position of the next character that isn't whitespace
+fn skip_whitespace(s: &[u8], pos: usize) -> usize {
+    pos + s.get(pos..).unwrap_or(&[]).iter().take_while(|c| c.is_ascii_whitespace()).count()
 }
 
-fn parse_fish(s: &[u8], depth: u8) -> (Vec<(u8, u8)>, usize) {
-    match s.get(0).unwrap() {
-        b'[' => {
-            let (mut left, i) = parse_fish(&s[1..], depth + 1);
-            let (right, j) = parse_fish(&s[i + 2..], depth + 1);
+// returns the parsed number and the position right after it
+// reduced numbers have no pair nested inside four pairs, the puzzle's examples
+// also show sums before their reduction, with pairs nested inside four pairs
+fn parse_fish(s: &[u8], pos: usize, depth: u8) -> Result<(Vec<(u32, u8)>, usize), FishError> {
+    let pos = skip_whitespace(s, pos);
+
+    match s.get(pos) {
+        Some(b'[') if depth >= 5 => Err(FishError { column: pos + 1, expected: "at most 5 nested pairs" }),
+        Some(b'[') => {
+            let (mut left, pos) = parse_fish(s, pos + 1, depth + 1)?;
+            let pos = skip_whitespace(s, pos);
+            expect(s, pos, b',', "\",\"")?;
+            let (right, pos) = parse_fish(s, pos + 1, depth + 1)?;
+            let pos = skip_whitespace(s, pos);
+            expect(s, pos, b']', "\"]\"")?;
             left.extend(right.into_iter());
-            (left, i + j + 3)
+            Ok((left, pos + 1))
         },
-        c @ b'0'..=b'9' => ([(*c - b'0', depth)].into_iter().collect(), 1),
-        _ => panic!(),
+        Some(b'0'..=b'9') => {
+            let digits = s[pos..].iter().take_while(|c| c.is_ascii_digit()).count();
+
+            // the digits are ASCII
+            match std::str::from_utf8(&s[pos..pos + digits]).unwrap().parse::<u8>() {
+                Ok(value) => Ok((vec![(value.into(), depth)], pos + digits)),
+                Err(_) => Err(FishError { column: pos + 1, expected: "a regular number up to 255" }),
+            }
+        },
+        _ => Err(FishError { column: pos + 1, expected: "a pair or a regular number" }),
+    }
+}
+
+impl FromStr for SnailfishNumber {
+    type Err = FishError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (values, end) = parse_fish(s.as_bytes(), 0, 0)?;
+
+        match skip_whitespace(s.as_bytes(), end) {
+            end if end == s.len() => Ok(SnailfishNumber { values }),
+            end => Err(FishError { column: end + 1, expected: "the end of the line" }),
+        }
+    }
+}
+
+impl Display for SnailfishNumber {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // the values are the leaves of the pairs from left to right, a value
+        // deeper than `depth` means a pair starts here
+        fn write(f: &mut std::fmt::Formatter<'_>, values: &[(u32, u8)], index: &mut usize, depth: u8) -> std::fmt::Result {
+            let (value, value_depth) = values[*index];
+
+            if value_depth == depth {
+                *index += 1;
+
+                return write!(f, "{}", value);
+            }
+
+            write!(f, "[")?;
+            write(f, values, index, depth + 1)?;
+            write!(f, ",")?;
+            write(f, values, index, depth + 1)?;
+            write!(f, "]")
+        }
+
+        write(f, &self.values, &mut 0, 0)
     }
 }
 
 #[aoc_generator(day18)]
-fn input_generator(input: &str) -> Input {
-    Input {
-        numbers: input.lines()
-            .map(|line| parse_fish(line.as_bytes(), 0).0)
-            .collect::<Vec<_>>(),
+fn input_generator(input: &str) -> Result<Input, ParseError> {
+    let numbers = parse::lines(input)
+        .map(|(n, line)| line.parse().map_err(|e: FishError| ParseError::new(18, n, e.column, e.expected)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // the parts sum the numbers
+    if numbers.is_empty() {
+        return Err(ParseError::new(18, 1, 1, "at least one snailfish number"));
     }
+
+    Ok(Input { numbers })
 }
 
 #[allow(unreachable_code)]
 #[test]
 fn part1_provided_exemple_input() {
-    assert_eq!(input_generator(exemple_raw_input()), Input {
+    assert_eq!(input_generator(Day18::EXAMPLE).unwrap(), Input {
         numbers: vec![
-            vec![(1,1), (1,1)],
-            vec![(2,1), (2,1)],
-            vec![(3,1), (3,1)],
-            vec![(4,1), (4,1)],
+            SnailfishNumber { values: vec![(1,1), (1,1)] },
+            SnailfishNumber { values: vec![(2,1), (2,1)] },
+            SnailfishNumber { values: vec![(3,1), (3,1)] },
+            SnailfishNumber { values: vec![(4,1), (4,1)] },
         ],
     });
 }
 
+#[test]
+fn input_errors() {
+    assert_eq!(input_generator("[1,2]\n[[1,2]3]"), Err(ParseError::new(18, 2, 7, "\",\"")));
+    assert_eq!(input_generator("[1,2]\n[[1,2],3"), Err(ParseError::new(18, 2, 9, "\"]\"")));
+    assert_eq!(input_generator("[1,a]"), Err(ParseError::new(18, 1, 4, "a pair or a regular number")));
+    assert_eq!(input_generator("[1,2]]"), Err(ParseError::new(18, 1, 6, "the end of the line")));
+    assert_eq!(input_generator(""), Err(ParseError::new(18, 1, 1, "at least one snailfish number")));
+    assert_eq!(
+        crate::solve(18, 1, "\n"),
+        Err(crate::SolveError::Parse(ParseError::new(18, 1, 1, "at least one snailfish number"))),
+    );
+    assert_eq!(input_generator("[1,2]\n[[[[[[1,2],3],4],5],6],7]"), Err(ParseError::new(18, 2, 6, "at most 5 nested pairs")));
+}
+
 type Output = u32;
 
 #[allow(unused_variables)]
 #[aoc(day18, part1)]
 
 fn part1(input: &Input) -> Output {
-    magnitude(input.numbers.iter().cloned().reduce(|l, r| add_fish(l, &r)).unwrap())
+    input.numbers.iter().sum::<SnailfishNumber>().magnitude()
 }
 
-fn add_fish(mut l: Vec<(u8, u8)>, r: &Vec<(u8, u8)>) -> Vec<(u8, u8)> {
-    l.extend(r);
+impl Add for SnailfishNumber {
+    type Output = SnailfishNumber;
+
+    fn add(self, other: Self) -> Self {
+        let mut sum = SnailfishNumber::pair(self, other);
 
-    for (_value, depth) in &mut l {
-        *depth += 1;
+        // the shortcuts only work for pairs nested inside at most five pairs,
+        // which is all that adding reduced numbers gives
+        if sum.values.iter().any(|&(_, depth)| depth > 5) {
+            while sum.reduce_once().is_some() {}
+
+            return sum;
+        }
+
+        SnailfishNumber { values: reduce_fish(reduce_fish(sum.values, true), false) }
+    }
+}
+
+// snailfish numbers have no zero, there must be at least one of them
+impl Sum for SnailfishNumber {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.reduce(|l, r| l + r).expect("at least one snailfish number")
     }
+}
 
-    reduce_fish(reduce_fish(l, true), false)
+impl<'a> Sum<&'a SnailfishNumber> for SnailfishNumber {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.cloned().sum()
+    }
 }
 
-fn reduce_fish(mut v: Vec<(u8, u8)>, first_pass: bool) -> Vec<(u8, u8)> {
-    let mut out: Vec<(u8, u8)> = vec![];
+fn reduce_fish(mut v: Vec<(u32, u8)>, first_pass: bool) -> Vec<(u32, u8)> {
+    let mut out: Vec<(u32, u8)> = vec![];
     let mut i = 0;
     while i < v.len() {
         let (value, depth) = v[i];
@@ -92,29 +225,108 @@ fn reduce_fish(mut v: Vec<(u8, u8)>, first_pass: bool) -> Vec<(u8, u8)> {
     out
 }
 
-fn magnitude(v: Vec<(u8, u8)>) -> u32 {
-    let mut stack = vec![];
+// one action of the reduction, the values are the ones before the action
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Step {
+    Explode { left: u32, right: u32 },
+    Split(u32),
+}
 
-    for (value, depth) in v {
-        stack.push((value as u32, depth));
-        while let Some(&[l, r]) = stack.get(stack.len().saturating_sub(2)..) {
-            if l.1 == r.1 {
-                stack.pop();
-                stack.pop();
-                stack.push((3 * l.0 + 2 * r.0, l.1 - 1));
-            } else {
-                break;
+impl Display for Step {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Step::Explode { left, right } => write!(f, "explode at pair [{},{}]", left, right),
+            Step::Split(value) => write!(f, "split at value {}", value),
+        }
+    }
+}
+
+// the numbers after each step of the reduction, one action at a time
+pub struct Reduction {
+    number: SnailfishNumber,
+}
+
+impl Iterator for Reduction {
+    type Item = (Step, SnailfishNumber);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let step = self.number.reduce_once()?;
+
+        Some((step, self.number.clone()))
+    }
+}
+
+impl SnailfishNumber {
+    // the pair of both numbers, without reducing it like an addition does
+    pub fn pair(mut left: Self, right: Self) -> Self {
+        left.values.extend(right.values);
+
+        for (_value, depth) in &mut left.values {
+            *depth += 1;
+        }
+
+        left
+    }
+
+    // follows the rules literally, one action at a time, while `+` takes shortcuts
+    pub fn reduction(self) -> Reduction {
+        Reduction { number: self }
+    }
+
+    // the leftmost pair nested inside four pairs explodes, otherwise the leftmost value of 10 or more splits
+    fn reduce_once(&mut self) -> Option<Step> {
+        let values = &mut self.values;
+        let explode = (0..values.len().saturating_sub(1))
+            .find(|&i| values[i].1 > 4 && values[i].1 == values[i + 1].1);
+
+        if let Some(i) = explode {
+            let ((left, depth), (right, _)) = (values[i], values[i + 1]);
+
+            if i > 0 {
+                values[i - 1].0 += left;
+            }
+
+            if let Some(after) = values.get_mut(i + 2) {
+                after.0 += right;
             }
+
+            values.splice(i..i + 2, [(0, depth - 1)]);
+
+            return Some(Step::Explode { left, right });
         }
+
+        let i = values.iter().position(|&(value, _)| value >= 10)?;
+        let (value, depth) = values[i];
+
+        values.splice(i..i + 1, [(value / 2, depth + 1), (value - value / 2, depth + 1)]);
+
+        Some(Step::Split(value))
     }
 
-    stack.pop().unwrap().0
+    pub fn magnitude(&self) -> u32 {
+        let mut stack = vec![];
+
+        for &(value, depth) in &self.values {
+            stack.push((value, depth));
+            while let Some(&[l, r]) = stack.get(stack.len().saturating_sub(2)..) {
+                if l.1 == r.1 {
+                    stack.pop();
+                    stack.pop();
+                    stack.push((3 * l.0 + 2 * r.0, l.1 - 1));
+                } else {
+                    break;
+                }
+            }
+        }
+
+        stack.pop().unwrap().0
+    }
 }
 
 #[allow(unreachable_code)]
 #[test]
 fn part1_provided_exemple() {
-    assert_eq!(part1(&input_generator(exemple_raw_input())), 445);
+    assert_eq!(part1(&input_generator(Day18::EXAMPLE).unwrap()), 445);
 }
 
 #[allow(unused_variables)]
@@ -123,10 +335,10 @@ fn part1_provided_exemple() {
 fn part2(input: &Input) -> Output {
     let mut max = 0;
 
-    for l in input.numbers.clone() {
-        for r in input.numbers.clone() {
-            max = std::cmp::max(max, magnitude(add_fish(l.clone(), &r)));
-            max = std::cmp::max(max, magnitude(add_fish(r.clone(), &l)));
+    for l in &input.numbers {
+        for r in &input.numbers {
+            max = std::cmp::max(max, (l.clone() + r.clone()).magnitude());
+            max = std::cmp::max(max, (r.clone() + l.clone()).magnitude());
         }
     }
 
@@ -136,14 +348,177 @@ fn part2(input: &Input) -> Output {
 #[allow(unreachable_code)]
 #[test]
 fn part2_provided_exemple() {
-    assert_eq!(part2(&input_generator(exemple_raw_input())), 100);
+    assert_eq!(part2(&input_generator(Day18::EXAMPLE).unwrap()), 100);
 }
 
-#[allow(unreachable_code)]
-#[allow(dead_code)]
-fn exemple_raw_input() -> &'static str {
-    "[1,1]
+#[test]
+fn display_round_trip() {
+    for number in [
+        "[1,2]",
+        "[[1,2],3]",
+        "[9,[8,7]]",
+        "[[1,9],[8,5]]",
+        "[[[[1,2],[3,4]],[[5,6],[7,8]]],9]",
+        "[[[9,[3,8]],[[0,9],6]],[[[3,7],[4,9]],3]]",
+        "[[[[1,3],[5,3]],[[1,3],[8,7]]],[[[4,9],[6,9]],[[8,2],[7,3]]]]",
+        "7",
+    ] {
+        assert_eq!(number.parse::<SnailfishNumber>().map(|n| n.to_string()), Ok(number.to_string()));
+    }
+
+    assert_eq!("[1,2]]".parse::<SnailfishNumber>(), Err(FishError { column: 6, expected: "the end of the line" }));
+    assert_eq!(
+        "[[1,2]3]".parse::<SnailfishNumber>().unwrap_err().to_string(),
+        "column 7: expected \",\"",
+    );
+}
+
+#[test]
+fn parse_numbers() {
+    let number = |s: &str| s.parse::<SnailfishNumber>().map(|n| n.to_string());
+    let error = |column, expected| Err(FishError { column, expected });
+
+    assert_eq!(number("[10,2]"), Ok("[10,2]".to_string()));
+    assert_eq!(number("[[[[0,7],4],[15,[0,13]]],[1,1]]"), Ok("[[[[0,7],4],[15,[0,13]]],[1,1]]".to_string()));
+    assert_eq!(number("[1, 2]"), Ok("[1,2]".to_string()));
+    assert_eq!(number(" [ [1 ,2],\t[ 3,255 ] ] "), Ok("[[1,2],[3,255]]".to_string()));
+    assert_eq!("[10, 2]".parse::<SnailfishNumber>(), Ok(SnailfishNumber { values: vec![(10, 1), (2, 1)] }));
+
+    assert_eq!(number("[1,256]"), error(4, "a regular number up to 255"));
+    assert_eq!(number("[1 2]"), error(4, "\",\""));
+    assert_eq!(number("[1, 2"), error(6, "\"]\""));
+    assert_eq!(number("[[1,2] , 3"), error(11, "\"]\""));
+    assert_eq!(number("[1,2] ]"), error(7, "the end of the line"));
+    assert_eq!(number("[,2]"), error(2, "a pair or a regular number"));
+    assert_eq!(number("  "), error(3, "a pair or a regular number"));
+    assert_eq!(number("[[[[[1,2],3],4],5],6]"), Ok("[[[[[1,2],3],4],5],6]".to_string()));
+    assert_eq!(number("[[[[[1,[2,3]],4],5],6],7]"), error(8, "at most 5 nested pairs"));
+    assert_eq!(number(&format!("{}1{}", "[".repeat(100_000), ",1]".repeat(100_000))), error(6, "at most 5 nested pairs"));
+}
+
+#[test]
+fn addition() {
+    let number = |s: &str| s.parse::<SnailfishNumber>().unwrap();
+
+    assert_eq!(
+        (number("[[[[4,3],4],4],[7,[[8,4],9]]]") + number("[1,1]")).to_string(),
+        "[[[[0,7],4],[[7,8],[6,0]]],[8,1]]",
+    );
+    assert_eq!(
+        ["[1,1]", "[2,2]", "[3,3]", "[4,4]", "[5,5]", "[6,6]"].into_iter().map(number).sum::<SnailfishNumber>().to_string(),
+        "[[[[5,0],[7,4]],[5,5]],[6,6]]",
+    );
+
+    let numbers = [
+        "[[[0,[4,5]],[0,0]],[[[4,5],[2,6]],[9,5]]]",
+        "[7,[[[3,7],[4,3]],[[6,3],[8,8]]]]",
+        "[[2,[[0,8],[3,4]]],[[[6,7],1],[7,[1,6]]]]",
+        "[[[[2,4],7],[6,[0,5]]],[[[6,8],[2,8]],[[2,1],[4,5]]]]",
+        "[7,[5,[[3,8],[1,4]]]]",
+        "[[2,[2,2]],[8,[8,1]]]",
+        "[2,9]",
+        "[1,[[[9,3],9],[[9,0],[0,7]]]]",
+        "[[[5,[7,4]],7],1]",
+        "[[[[4,2],2],6],[8,7]]",
+    ].map(number);
+
+    assert_eq!(numbers.iter().sum::<SnailfishNumber>().to_string(), "[[[[8,7],[7,7]],[[8,6],[7,7]]],[[[0,7],[6,6]],[8,7]]]");
+
+    // the sums of the worked examples can be added too
+    let sum = number("[[[[[9,8],1],2],3],4]") + number("[[[[[4,3],4],4],[7,[[8,4],9]]],[1,1]]");
+
+    assert_eq!(sum.to_string(), "[[[[5,0],9],[6,7]],[[[5,5],[5,0]],[[6,6],9]]]");
+    assert_eq!(sum.magnitude(), 1821);
+
+    // 200 + 100 and then 200 + 200 don't fit in a byte
+    let sum = number("[[[[200,200],100],1],1]") + number("[1,1]");
+
+    assert_eq!(sum.to_string(), "[[[[6,7],[7,7]],[[7,7],[7,7]]],[[[7,7],[7,7]],[[0,8],[8,8]]]]");
+    assert_eq!(sum.magnitude(), 4106);
+}
+
+#[test]
+fn reduction() {
+    let number = |s: &str| s.parse::<SnailfishNumber>().unwrap();
+    let sum = number("[[[[[4,3],4],4],[7,[[8,4],9]]],[1,1]]");
+
+    assert_eq!(sum, SnailfishNumber::pair(number("[[[[4,3],4],4],[7,[[8,4],9]]]"), number("[1,1]")));
+
+    let steps = sum.reduction()
+        .map(|(step, number)| format!("after {}: {}", step, number))
+        .collect::<Vec<_>>();
+
+    assert_eq!(steps, [
+        "after explode at pair [4,3]: [[[[0,7],4],[7,[[8,4],9]]],[1,1]]",
+        "after explode at pair [8,4]: [[[[0,7],4],[15,[0,13]]],[1,1]]",
+        "after split at value 15: [[[[0,7],4],[[7,8],[0,13]]],[1,1]]",
+        "after split at value 13: [[[[0,7],4],[[7,8],[0,[6,7]]]],[1,1]]",
+        "after explode at pair [6,7]: [[[[0,7],4],[[7,8],[6,0]]],[8,1]]",
+    ]);
+
+    assert_eq!(number("[[[[0,7],4],[[7,8],[6,0]]],[8,1]]").reduction().next(), None);
+    assert_eq!(
+        number("[[[[[9,8],1],2],3],4]").reduction().collect::<Vec<_>>(),
+        [(Step::Explode { left: 9, right: 8 }, number("[[[[0,9],2],3],4]"))],
+    );
+
+    // the explodes first then splits of `+` end up with the same numbers
+    let numbers = [
+        "[[[0,[4,5]],[0,0]],[[[4,5],[2,6]],[9,5]]]",
+        "[7,[[[3,7],[4,3]],[[6,3],[8,8]]]]",
+        "[[2,[[0,8],[3,4]]],[[[6,7],1],[7,[1,6]]]]",
+        "[[[[2,4],7],[6,[0,5]]],[[[6,8],[2,8]],[[2,1],[4,5]]]]",
+        "[7,[5,[[3,8],[1,4]]]]",
+        "[[2,[2,2]],[8,[8,1]]]",
+        "[2,9]",
+        "[1,[[[9,3],9],[[9,0],[0,7]]]]",
+        "[[[5,[7,4]],7],1]",
+        "[[[[4,2],2],6],[8,7]]",
+    ].map(number);
+
+    for l in &numbers {
+        for r in &numbers {
+            let pair = SnailfishNumber::pair(l.clone(), r.clone());
+            let reduced = pair.clone().reduction().last().map_or(pair, |(_, number)| number);
+
+            assert_eq!(reduced, l.clone() + r.clone());
+        }
+    }
+}
+
+#[test]
+fn magnitude() {
+    let magnitude = |s: &str| s.parse::<SnailfishNumber>().unwrap().magnitude();
+
+    assert_eq!(magnitude("[9,1]"), 29);
+    assert_eq!(magnitude("[[1,2],[[3,4],5]]"), 143);
+    assert_eq!(magnitude("[[[[0,7],4],[[7,8],[6,0]]],[8,1]]"), 1384);
+    assert_eq!(magnitude("[[[[8,7],[7,7]],[[8,6],[7,7]]],[[[0,7],[6,6]],[8,7]]]"), 3488);
+}
+
+pub struct Day18;
+
+impl Puzzle for Day18 {
+    const DAY: u8 = 18;
+    const EXAMPLE: &'static str = "[1,1]
 [2,2]
 [3,3]
-[4,4]"
+[4,4]";
+    const EXAMPLE_ANSWERS: [Option<Answer>; 2] = [Some(Answer::Number(445)), Some(Answer::Number(100))];
+
+    type Input = Input;
+    type Output1 = Output;
+    type Output2 = Output;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
 }
@@ -2,8 +2,10 @@ use std::{cmp::Ordering, collections::{HashSet, HashMap}};
 
 use parse_display::{Display, FromStr};
 
+use crate::{parse::{self, ParseError}, puzzle::Puzzle, solve::Answer};
+
 #[derive(Debug, PartialEq, Clone)]
-struct Input {
+pub struct Input {
     scanners: Vec<Vec<Point>>,
 }
 
@@ -25,29 +27,52 @@ pub struct PointU {
 
 #[allow(unused_variables)]
 #[aoc_generator(day19)]
-fn input_generator(input: &str) -> Input {
+fn input_generator(input: &str) -> Result<Input, ParseError> {
     let mut scanners: Vec<Vec<Point>> = Vec::new();
 
-    for line in input.lines().map(|l| l.trim()).filter(|l| !l.is_empty()) {
-        if line.starts_with("--- scanner") {
+    for (n, line) in parse::lines(input) {
+        let beacon = line.trim();
+
+        if beacon.is_empty() {
+            continue;
+        }
+
+        if beacon.starts_with("--- scanner") {
             scanners.push(Vec::new());
         } else {
-            let scanner = scanners.last_mut().unwrap();
-            scanner.push(line.parse::<Point>().unwrap())
+            let scanner = scanners.last_mut()
+                .ok_or_else(|| ParseError::new(19, n, 1, "a scanner header (--- scanner n ---)"))?;
+            scanner.push(parse::parse(19, (n, line), beacon, "a beacon position (x,y,z)")?)
         }
     }
 
-    Input { scanners }
+    // the first scanner is the reference for the others
+    if scanners.is_empty() {
+        return Err(ParseError::new(19, 1, 1, "at least one scanner"));
+    }
+
+    Ok(Input { scanners })
 }
 
 #[test]
 fn part1_provided_exemple_input() {
-    let i = input_generator(exemple_raw_input());
+    let i = input_generator(Day19::EXAMPLE).unwrap();
 
     assert_eq!(i.scanners[0][0], Point { x: 404, y: -588, z: -901 });
     assert_eq!(i.scanners.len(), 5);
 }
 
+#[test]
+fn input_errors() {
+    assert_eq!(input_generator("404,-588,-901"), Err(ParseError::new(19, 1, 1, "a scanner header (--- scanner n ---)")));
+    assert_eq!(
+        input_generator("--- scanner 0 ---\n 404,-588"),
+        Err(ParseError::new(19, 2, 2, "a beacon position (x,y,z)")),
+    );
+    assert_eq!(input_generator("\n"), Err(ParseError::new(19, 1, 1, "at least one scanner")));
+    assert_eq!(crate::solve(19, 1, ""), Err(crate::SolveError::Parse(ParseError::new(19, 1, 1, "at least one scanner"))));
+}
+
 pub fn rotate(p: Point, i: usize) -> Point {
     let p = match i >> 2 {
         0 => Point { x: p.x, y: p.y, z: p.z },
@@ -191,7 +216,7 @@ fn part1(input: &Input) -> usize {
 
 #[test]
 fn part1_provided_exemple() {
-    assert_eq!(part1(&input_generator(exemple_raw_input())), 79);
+    assert_eq!(part1(&input_generator(Day19::EXAMPLE).unwrap()), 79);
 }
 
 #[allow(unused_variables)]
@@ -203,13 +228,14 @@ fn part2(input: &Input) -> u32 {
 
 #[test]
 fn part2_provided_exemple() {
-    assert_eq!(part2(&input_generator(exemple_raw_input())), 15);
+    assert_eq!(part2(&input_generator(Day19::EXAMPLE).unwrap()), 15);
 }
 
-#[allow(unreachable_code)]
-#[allow(dead_code)]
-fn exemple_raw_input() -> &'static str {
-    "--- scanner 0 ---
+pub struct Day19;
+
+impl Puzzle for Day19 {
+    const DAY: u8 = 19;
+    const EXAMPLE: &'static str = "--- scanner 0 ---
 404,-588,-901
 528,-643,409
 -838,591,734
@@ -344,5 +370,22 @@ fn exemple_raw_input() -> &'static str {
 839,-516,451
 891,-625,532
 -652,-548,-490
-30,-46,-14"
+30,-46,-14";
+    const EXAMPLE_ANSWERS: [Option<Answer>; 2] = [Some(Answer::Number(79)), Some(Answer::Number(3621))];
+
+    type Input = Input;
+    type Output1 = usize;
+    type Output2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
 }
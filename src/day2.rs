@@ -1,5 +1,7 @@
 use parse_display::{Display, FromStr};
 
+use crate::{parse::{self, ParseError}, puzzle::Puzzle, solve::Answer};
+
 #[derive(Display, FromStr, PartialEq, Debug)]
 pub enum Command {
     #[display("forward {0}")]
@@ -11,10 +13,9 @@ pub enum Command {
 }
 
 #[aoc_generator(day2)]
-pub fn input_generator(input: &str) -> Vec<Command> {
-    input
-        .lines()
-        .map(|l| l.parse().unwrap())
+pub fn input_generator(input: &str) -> Result<Vec<Command>, ParseError> {
+    parse::lines(input)
+        .map(|line| parse::parse(2, line, line.1, "forward, down or up followed by a number"))
         .collect()
 }
 
@@ -42,17 +43,40 @@ pub fn part2(commands: &[Command]) -> u32 {
     x * z
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+pub struct Day2;
 
-    const EXEMPLE_RAW_INPUT: &str = "forward 5
+impl Puzzle for Day2 {
+    const DAY: u8 = 2;
+    const EXAMPLE: &'static str = "forward 5
 down 5
 forward 8
 up 3
 down 8
 forward 2
 ";
+    const EXAMPLE_ANSWERS: [Option<Answer>; 2] = [Some(Answer::Number(900)), Some(Answer::Number(150))];
+
+    type Input = Vec<Command>;
+    type Output1 = u32;
+    type Output2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     const EXEMPLE_PARSED_INPUT: &[Command] = &[
         Command::Forward(5),
         Command::Down(5),
@@ -74,6 +98,14 @@ forward 2
 
     #[test]
     fn part1_provided_exemple_input() {
-        assert_eq!(input_generator(EXEMPLE_RAW_INPUT), EXEMPLE_PARSED_INPUT);
+        assert_eq!(input_generator(Day2::EXAMPLE).unwrap(), EXEMPLE_PARSED_INPUT);
+    }
+
+    #[test]
+    fn input_errors() {
+        assert_eq!(
+            input_generator("forward 5\nbackward 2"),
+            Err(ParseError::new(2, 2, 1, "forward, down or up followed by a number")),
+        );
     }
 }
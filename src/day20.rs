@@ -1,16 +1,205 @@
+use std::fmt::Display;
+
+use crate::{parse::{self, ParseError}, puzzle::Puzzle, solve::Answer};
+
 #[derive(Debug, PartialEq)]
-struct Input {}
+pub struct Input {
+    algorithm: Vec<bool>,
+    image: Image,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+struct Image {
+    pixels: Vec<Vec<bool>>,
+    background: bool,
+}
+
+fn parse_pixels((n, line): (usize, &str)) -> Result<Vec<bool>, ParseError> {
+    line.chars()
+        .enumerate()
+        .map(|(column, c)| match c {
+            '#' => Ok(true),
+            '.' => Ok(false),
+            _ => Err(ParseError::new(20, n, column + 1, "a pixel (# or .)")),
+        })
+        .collect()
+}
 
+// the algorithm can be wrapped on several lines (like in the puzzle text),
+// it ends at the first empty line
 #[allow(unused_variables)]
 #[aoc_generator(day20)]
-fn input_generator(input: &str) -> Input {
-    todo!()
+fn input_generator(input: &str) -> Result<Input, ParseError> {
+    let mut lines = parse::lines(input).map(|(n, line)| (n, line.trim_end()));
+
+    let mut algorithm = vec![];
+    let mut n = 1;
+
+    for line in lines.by_ref().take_while(|(_, line)| !line.is_empty()) {
+        n = line.0;
+        algorithm.extend(parse_pixels(line)?);
+    }
+
+    if algorithm.len() != 512 {
+        return Err(ParseError::new(20, n, 1, "an enhancement algorithm of 512 pixels"));
+    }
+
+    Ok(Input {
+        algorithm,
+        image: Image {
+            pixels: lines
+                .filter(|(_, line)| !line.is_empty())
+                .map(parse_pixels)
+                .collect::<Result<Vec<_>, _>>()?,
+            background: false,
+        },
+    })
+}
+
+impl Display for Image {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.pixels.iter()
+            .map(|row| row.iter()
+                .map(|&lit| if lit { '#' } else { '.' })
+                .collect::<String>()
+            )
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
 }
 
-#[allow(unreachable_code)]
 #[test]
 fn part1_provided_exemple_input() {
-    assert_eq!(input_generator(exemple_raw_input()), todo!());
+    let input = input_generator(Day20::EXAMPLE).unwrap();
+
+    assert_eq!(input.algorithm.len(), 512);
+    assert_eq!(input.algorithm[..5], [false, false, true, false, true]);
+    assert!(input.algorithm[34]);
+    assert!(!input.image.background);
+    assert_eq!(format!("{}", input.image), "#..#.
+#....
+##..#
+..#..
+..###");
+}
+
+#[test]
+fn input_errors() {
+    assert_eq!(input_generator("..#.#\n\n#..#."), Err(ParseError::new(20, 1, 1, "an enhancement algorithm of 512 pixels")));
+
+    let algorithm = ".".repeat(512);
+
+    assert_eq!(
+        input_generator(&format!("{}\n\n#..#.\n#.o..", algorithm)),
+        Err(ParseError::new(20, 4, 3, "a pixel (# or .)")),
+    );
+}
+
+impl Image {
+    pub fn at(&self, row: isize, column: isize) -> bool {
+        if row < 0 || column < 0 {
+            return self.background;
+        }
+
+        self.pixels
+            .get(row as usize)
+            .and_then(|line| line.get(column as usize))
+            .copied()
+            .unwrap_or(self.background)
+    }
+
+    fn index(&self, row: isize, column: isize) -> usize {
+        let mut index = 0;
+
+        for r in row - 1..=row + 1 {
+            for c in column - 1..=column + 1 {
+                index = index * 2 + self.at(r, c) as usize;
+            }
+        }
+
+        index
+    }
+
+    // the image grows by one pixel on each side at every step. every pixel
+    // of the infinite background is surrounded by background pixels, so they
+    // all become either algorithm[0] or algorithm[511]
+    pub fn enhance(&self, algorithm: &[bool]) -> Self {
+        let height = self.pixels.len() as isize;
+        let width = self.pixels.first().map_or(0, |line| line.len()) as isize;
+
+        Self {
+            pixels: (-1..=height)
+                .map(|row| (-1..=width)
+                    .map(|column| algorithm[self.index(row, column)])
+                    .collect::<Vec<_>>()
+                )
+                .collect::<Vec<_>>(),
+            background: algorithm[if self.background { 511 } else { 0 }],
+        }
+    }
+
+    pub fn lit_count(&self) -> usize {
+        assert!(!self.background, "an infinite number of pixels are lit");
+
+        self.pixels.iter()
+            .map(|line| line.iter().filter(|&&lit| lit).count())
+            .sum()
+    }
+}
+
+impl Input {
+    fn enhanced(&self, times: usize) -> Image {
+        (0..times).fold(self.image.clone(), |image, _| image.enhance(&self.algorithm))
+    }
+}
+
+#[test]
+fn test_enhance() {
+    let input = input_generator(Day20::EXAMPLE).unwrap();
+
+    assert_eq!(format!("{}", input.enhanced(1)), ".##.##.
+#..#.#.
+##.#..#
+####..#
+.#..##.
+..##..#
+...#.#.");
+
+    assert_eq!(format!("{}", input.enhanced(2)), ".......#.
+.#..#.#..
+#.#...###
+#...##.#.
+#.....#.#
+.#.#####.
+..#.#####
+...##.##.
+....###..");
+}
+
+#[test]
+fn test_flipping_background() {
+    let mut algorithm = vec![false; 512];
+    algorithm[0] = true;
+
+    let input = Input {
+        algorithm,
+        image: Image {
+            pixels: vec![vec![false]],
+            background: false,
+        },
+    };
+
+    let image = input.enhanced(1);
+
+    assert!(image.background);
+    assert_eq!(format!("{}", image), "###
+###
+###");
+
+    let image = input.enhanced(2);
+
+    assert!(!image.background);
+    assert_eq!(image.lit_count(), 0);
 }
 
 type Output = usize;
@@ -19,30 +208,58 @@ type Output = usize;
 #[aoc(day20, part1)]
 
 fn part1(input: &Input) -> Output {
-    todo!()
+    input.enhanced(2).lit_count()
 }
 
-#[allow(unreachable_code)]
 #[test]
 fn part1_provided_exemple() {
-    assert_eq!(part1(&input_generator(exemple_raw_input())), todo!());
+    assert_eq!(part1(&input_generator(Day20::EXAMPLE).unwrap()), 35);
 }
 
 #[allow(unused_variables)]
 #[aoc(day20, part2)]
 
 fn part2(input: &Input) -> Output {
-    todo!()
+    input.enhanced(50).lit_count()
 }
 
-#[allow(unreachable_code)]
 #[test]
 fn part2_provided_exemple() {
-    assert_eq!(part2(&input_generator(exemple_raw_input())), todo!());
+    assert_eq!(part2(&input_generator(Day20::EXAMPLE).unwrap()), 3351);
 }
 
-#[allow(unreachable_code)]
-#[allow(dead_code)]
-fn exemple_raw_input() -> &'static str {
-    todo!()
+pub struct Day20;
+
+impl Puzzle for Day20 {
+    const DAY: u8 = 20;
+    const EXAMPLE: &'static str = "..#.#..#####.#.#.#.###.##.....###.##.#..###.####..#####..#....#..#..##..##
+#..######.###...####..#..#####..##..#.#####...##.#.#..#.##..#.#......#.###
+.######.###.####...#.##.##..#..#..#####.....#.#....###..#.##......#.....#.
+.#..#..##..#...##.######.####.####.#.#...#.......#..#.#.#...####.##.#.....
+.#..#...##.#.##..#...##.#.##..###.#......#.#.......#.#.#.####.###.##...#..
+...####.#..#..#.##.#....##..#.####....##...##..#...#......#.#.......#.....
+..##..####..#...#.#.#...##..#.#..###..#####........#..####......#..#
+
+#..#.
+#....
+##..#
+..#..
+..###";
+    const EXAMPLE_ANSWERS: [Option<Answer>; 2] = [Some(Answer::Number(35)), Some(Answer::Number(3351))];
+
+    type Input = Input;
+    type Output1 = Output;
+    type Output2 = Output;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
 }
@@ -1,7 +1,9 @@
 use std::collections::HashMap;
 
+use crate::{parse::{self, ParseError}, puzzle::Puzzle, solve::Answer};
+
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
-struct Game {
+pub struct Game {
     first_player_is_next: bool,
     players: (Player, Player)
 }
@@ -14,23 +16,35 @@ struct Player {
 
 #[allow(unused_variables)]
 #[aoc_generator(day21)]
-fn input_generator(input: &str) -> Game {
-    let players = input.lines()
-        .map(|line| line.split_once(": ").map(|(_, n)| Player {
-            position: n.parse::<usize>().unwrap() - 1,
-            score: 0,
-        }).unwrap())
-        .collect::<Vec<_>>();
-        
-    Game {
-        first_player_is_next: true,
-        players: (players[0].clone(), players[1].clone()),
+fn input_generator(input: &str) -> Result<Game, ParseError> {
+    let players = parse::lines(input)
+        .map(|line| {
+            let expected = "a starting position (Player n starting position: 1-10)";
+            let (_, n) = line.1.split_once(": ")
+                .ok_or_else(|| ParseError::new(21, line.0, 1, expected))?;
+
+            match parse::parse::<usize>(21, line, n, expected)? {
+                position @ 1..=10 => Ok(Player {
+                    position: position - 1,
+                    score: 0,
+                }),
+                _ => Err(ParseError::new(21, line.0, parse::column(line.1, n), expected)),
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    match players[..] {
+        [player1, player2] => Ok(Game {
+            first_player_is_next: true,
+            players: (player1, player2),
+        }),
+        _ => Err(ParseError::new(21, std::cmp::min(players.len(), 2) + 1, 1, "2 players")),
     }
 }
 
 #[test]
 fn part1_provided_exemple_input() {
-    assert_eq!(input_generator(exemple_raw_input()), Game {
+    assert_eq!(input_generator(Day21::EXAMPLE).unwrap(), Game {
         first_player_is_next: true,
         players: (
             Player {
@@ -45,8 +59,18 @@ fn part1_provided_exemple_input() {
     });
 }
 
+#[test]
+fn input_errors() {
+    let expected = "a starting position (Player n starting position: 1-10)";
+
+    assert_eq!(input_generator("Player 1 starting position 4"), Err(ParseError::new(21, 1, 1, expected)));
+    assert_eq!(input_generator("Player 1 starting position: four"), Err(ParseError::new(21, 1, 29, expected)));
+    assert_eq!(input_generator("Player 1 starting position: 11"), Err(ParseError::new(21, 1, 29, expected)));
+    assert_eq!(input_generator("Player 1 starting position: 4"), Err(ParseError::new(21, 2, 1, "2 players")));
+}
+
 impl Game {
-    pub fn after_next_turn(&self, dice: &mut impl Dice) -> Self {
+    fn after_next_turn(&self, dice: &mut impl Dice) -> Self {
         let mut game_after_next_turn = self.clone();
 
         if self.first_player_is_next {
@@ -145,7 +169,7 @@ fn part1(game: &Game) -> Output {
 #[allow(unreachable_code)]
 #[test]
 fn part1_provided_exemple() {
-    assert_eq!(part1(&input_generator(exemple_raw_input())), 739785);
+    assert_eq!(part1(&input_generator(Day21::EXAMPLE).unwrap()), 739785);
 }
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
@@ -331,12 +355,30 @@ fn part2(game: &Game) -> Output {
 #[allow(unreachable_code)]
 #[test]
 fn part2_provided_exemple() {
-    assert_eq!(part2(&input_generator(exemple_raw_input())), 444356092776315);
+    assert_eq!(part2(&input_generator(Day21::EXAMPLE).unwrap()), 444356092776315);
 }
 
-#[allow(unreachable_code)]
-#[allow(dead_code)]
-fn exemple_raw_input() -> &'static str {
-    "Player 1 starting position: 4
-Player 2 starting position: 8"
+pub struct Day21;
+
+impl Puzzle for Day21 {
+    const DAY: u8 = 21;
+    const EXAMPLE: &'static str = "Player 1 starting position: 4
+Player 2 starting position: 8";
+    const EXAMPLE_ANSWERS: [Option<Answer>; 2] = [Some(Answer::Number(739785)), Some(Answer::Number(444356092776315))];
+
+    type Input = Game;
+    type Output1 = Output;
+    type Output2 = Output;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
 }
@@ -1,7 +1,9 @@
-use std::{str::FromStr, convert::Infallible};
+use std::str::FromStr;
 use std::cmp::{min, max};
 use parse_display::{Display, FromStr};
 
+use crate::{parse::{self, ParseError}, puzzle::Puzzle, solve::Answer};
+
 #[derive(Display, FromStr, PartialEq, Debug, Clone, Copy)]
 #[display("{start}..{end}")]
 struct CuboidAxisRange {
@@ -490,32 +492,32 @@ fn test_parse_reboot_step() {
 }
 
 #[derive(Debug, PartialEq)]
-struct Input {
+pub struct Input {
     steps: Vec<RebootStep>,
 }
 
 impl FromStr for Input {
-    type Err = Infallible;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         Ok(Input {
-            steps: s.lines()
-                .map(|line| line.parse::<RebootStep>().unwrap())
-                .collect::<Vec<_>>(),
+            steps: parse::lines(s)
+                .map(|line| parse::parse(22, line, line.1, "a reboot step (on|off x=a..b,y=c..d,z=e..f)"))
+                .collect::<Result<Vec<_>, _>>()?,
         })
     }
 }
 
 #[allow(unused_variables)]
 #[aoc_generator(day22)]
-fn input_generator(input: &str) -> Input {
-    input.parse::<Input>().unwrap()
+fn input_generator(input: &str) -> Result<Input, ParseError> {
+    input.parse::<Input>()
 }
 
 #[allow(unreachable_code)]
 #[test]
 fn part1_provided_exemple_input() {
-    assert_eq!(input_generator(exemple_raw_input()), Input {
+    assert_eq!(input_generator(Day22::EXAMPLE).unwrap(), Input {
         steps: vec![
             RebootStep::On(Cuboid {
                 x: CuboidAxisRange {
@@ -631,6 +633,14 @@ impl Reactor {
             .collect::<Vec<_>>()
     }
 }
+#[test]
+fn input_errors() {
+    assert_eq!(
+        input_generator("on x=10..12,y=10..12,z=10..12\ntoggle x=11..13,y=11..13,z=11..13"),
+        Err(ParseError::new(22, 2, 1, "a reboot step (on|off x=a..b,y=c..d,z=e..f)")),
+    );
+}
+
 type Output = usize;
 
 #[allow(unused_variables)]
@@ -647,8 +657,8 @@ fn part1(input: &Input) -> Output {
 #[allow(unreachable_code)]
 #[test]
 fn part1_provided_exemple() {
-    assert_eq!(part1(&input_generator(exemple_raw_input())), 39);
-    assert_eq!(part1(&input_generator(larger_exemple_raw_input())), 590784);
+    assert_eq!(part1(&input_generator(Day22::EXAMPLE).unwrap()), 39);
+    assert_eq!(part1(&input_generator(larger_exemple_raw_input()).unwrap()), 590784);
 }
 
 #[allow(unused_variables)]
@@ -665,17 +675,8 @@ fn part2(input: &Input) -> Output {
 #[allow(unreachable_code)]
 #[test]
 fn part2_provided_exemple() {
-    assert_eq!(part2(&input_generator(exemple_raw_input())), 39);
-    assert_eq!(part2(&input_generator(larger_exemple_raw_input())), 39769202357779);
-}
-
-#[allow(unreachable_code)]
-#[allow(dead_code)]
-fn exemple_raw_input() -> &'static str {
-    "on x=10..12,y=10..12,z=10..12
-on x=11..13,y=11..13,z=11..13
-off x=9..11,y=9..11,z=9..11
-on x=10..10,y=10..10,z=10..10"
+    assert_eq!(part2(&input_generator(Day22::EXAMPLE).unwrap()), 39);
+    assert_eq!(part2(&input_generator(larger_exemple_raw_input()).unwrap()), 39769202357779);
 }
 
 #[allow(unreachable_code)]
@@ -704,3 +705,30 @@ on x=-41..9,y=-7..43,z=-33..15
 on x=-54112..-39298,y=-85059..-49293,z=-27449..7877
 on x=967..23432,y=45373..81175,z=27513..53682"
 }
+
+pub struct Day22;
+
+impl Puzzle for Day22 {
+    const DAY: u8 = 22;
+    const EXAMPLE: &'static str = "on x=10..12,y=10..12,z=10..12
+on x=11..13,y=11..13,z=11..13
+off x=9..11,y=9..11,z=9..11
+on x=10..10,y=10..10,z=10..10";
+    const EXAMPLE_ANSWERS: [Option<Answer>; 2] = [Some(Answer::Number(39)), Some(Answer::Number(39))];
+
+    type Input = Input;
+    type Output1 = Output;
+    type Output2 = Output;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
@@ -1,405 +1,864 @@
-use std::{str::FromStr, convert::Infallible, collections::{VecDeque, BinaryHeap, HashMap}, hash::Hash};
+use std::{fmt::Display, str::FromStr};
+
+use crate::{parse::{self, ParseError}, puzzle::Puzzle, search::{self, Path}, solve::Answer};
+
+// what doesn't change while the amphipods move around
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct Layout {
+    hallway: usize,
+    // position in the hallway right above each room, from left to right
+    entrances: Vec<usize>,
+    depth: usize,
+    // energy per step of each amphipod type
+    costs: Vec<u64>,
+}
+
+// amphipods are the index of their type, which is also the index of their room (A → 0),
+// rooms go from the bottom to the top
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+struct Burrow {
+    rooms: Vec<Vec<u8>>,
+    hallway: Vec<Option<u8>>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Input {
+    layout: Layout,
+    burrow: Burrow,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Place {
+    // counted from the left of the hallway, starting at 0
+    Hallway(usize),
+    // named after the amphipods that belong there, A being the leftmost
+    Room(usize),
+}
 
 #[derive(Debug, PartialEq, Eq, Clone)]
-struct Input {
-    rooms: [VecDeque<u32>; 4],
-    hallway: [u32; 11],
-    cost: u32,
+pub enum BurrowError {
+    // the amphipods block each other in the hallway
+    Deadlock,
+    // the rows don't have a known amphipod for each room
+    Unfold { rooms: usize, row: String },
+    // `with_costs` needs one cost per amphipod type
+    Costs { types: usize, costs: usize },
+    // the energy spent by the amphipods may not fit in a u64
+    Overflow,
 }
 
-impl Default for Input {
-    fn default() -> Self {
-        Self {
-            rooms: [
-                VecDeque::from([]),
-                VecDeque::from([]),
-                VecDeque::from([]),
-                VecDeque::from([]),
-            ],
-            hallway: [0; 11],
-            cost: 0,
+impl std::fmt::Display for BurrowError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BurrowError::Deadlock => write!(f, "the amphipods can't all get to their rooms"),
+            BurrowError::Unfold { rooms, row } => write!(f, "can't unfold the row {} in {} rooms", row, rooms),
+            BurrowError::Costs { types, costs } => write!(f, "expected {} costs, one per amphipod type, not {}", types, costs),
+            BurrowError::Overflow => write!(f, "the energy spent can overflow"),
         }
     }
 }
 
+impl std::error::Error for BurrowError {}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Move {
+    pub amphipod: char,
+    pub from: Place,
+    pub to: Place,
+    pub energy: u64,
+}
+
+fn amphipod(c: char, types: usize) -> Option<u8> {
+    match c {
+        'A'..='Z' if ((c as u8 - b'A') as usize) < types => Some(c as u8 - b'A'),
+        _ => None,
+    }
+}
+
+fn amphipod_name(amphipod: u8) -> char {
+    (b'A' + amphipod) as char
+}
+
+// eg: "an amphipod (A, B, C or D)"
+fn expected_amphipod(types: usize) -> String {
+    let names = (0..types as u8).map(|amphipod| amphipod_name(amphipod).to_string()).collect::<Vec<_>>();
+
+    match names.split_last() {
+        Some((last, [])) => format!("an amphipod ({})", last),
+        Some((last, names)) => format!("an amphipod ({} or {})", names.join(", "), last),
+        None => "an amphipod".to_string(),
+    }
+}
+
+// 10^20 doesn't fit in a u64
+const MAX_TYPES: usize = 20;
+
 impl FromStr for Input {
-    type Err = Infallible;
+    type Err = ParseError;
 
-    // already count the cost of getting the amphipods out and back in 
-    // so that we can just move them around in the hallway.
-    // then we just have to move each amphipod in front of their rooms
+    // the first row of rooms tells where the rooms are, all the rows below it
+    // down to the bottom wall are part of the rooms
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut lines = s.lines();
-        
-        lines.next();
-        lines.next();
-        
-        let positions = lines
-            .flat_map(|line| 
-                line
-                    .split(&['#', ' '][..])
-                    .filter_map(|part| part.chars().next())
-                    .map(|c| 10_u32.pow(c as u32 - 'A' as u32))
-                    .enumerate()
-                    .collect::<Vec<_>>(),
+        let lines = parse::lines(s).collect::<Vec<_>>();
+
+        let hallway = lines.get(1)
+            .map(|(_, line)| line.trim_end().chars().collect::<Vec<_>>())
+            .filter(|hallway| hallway.len() > 2)
+            .ok_or_else(|| ParseError::new(23, 2, 1, "a hallway (#...........#)"))?;
+
+        let room_columns = lines.get(2)
+            .map(|(_, line)| line.chars()
+                .enumerate()
+                .filter(|&(_, c)| c != '#' && c != ' ')
+                .map(|(column, _)| column)
+                .collect::<Vec<_>>()
             )
-            .collect::<Vec<_>>();
-        
-        Ok(Input::from_vec(positions))
+            .filter(|columns| !columns.is_empty())
+            .ok_or_else(|| ParseError::new(23, 3, 1, "a row of rooms (###B#C#B#D###)"))?;
+
+        let types = room_columns.len();
+
+        // the cost of the last type is 10^(types - 1)
+        if let Some(&column) = room_columns.get(MAX_TYPES) {
+            return Err(ParseError::new(23, 3, column + 1, format!("at most {} rooms", MAX_TYPES)));
+        }
+
+        if let Some(&column) = room_columns.iter().find(|&&column| column == 0 || column >= hallway.len() - 1) {
+            return Err(ParseError::new(23, 3, column + 1, "a room below the hallway"));
+        }
+
+        let mut rooms = vec![vec![]; types];
+        let mut depth = 0;
+
+        for &(n, line) in &lines[2..] {
+            let chars = line.chars().collect::<Vec<_>>();
+
+            if chars.iter().all(|&c| c == '#' || c == ' ') {
+                break;
+            }
+
+            if let Some(column) = chars.iter()
+                .enumerate()
+                .position(|(column, &c)| c != '#' && c != ' ' && !room_columns.contains(&column))
+            {
+                return Err(ParseError::new(23, n, column + 1, "a wall (#)"));
+            }
+
+            for (room, &column) in room_columns.iter().enumerate() {
+                match chars.get(column) {
+                    // empty spaces can only be above the amphipods
+                    Some('.') if rooms[room].is_empty() => {},
+                    Some(&c) if c != '#' && c != ' ' => match amphipod(c, types) {
+                        Some(amphipod) => rooms[room].insert(0, amphipod),
+                        None => return Err(ParseError::new(23, n, column + 1, expected_amphipod(types))),
+                    },
+                    _ => return Err(ParseError::new(23, n, 1, format!("an amphipod in each of the {} rooms", types))),
+                }
+            }
+
+            depth += 1;
+        }
+
+        let entrances = room_columns.iter().map(|column| column - 1).collect::<Vec<_>>();
+
+        let hallway = hallway[1..hallway.len() - 1].iter()
+            .enumerate()
+            .map(|(position, &c)| match (c, amphipod(c, types)) {
+                ('.', _) => Ok(None),
+                (_, Some(_)) if entrances.contains(&position) => {
+                    Err(ParseError::new(23, 2, position + 2, "an empty space above a room (.)"))
+                },
+                (_, Some(amphipod)) => Ok(Some(amphipod)),
+                (_, None) => Err(ParseError::new(23, 2, position + 2, format!("an empty space (.) or {}", expected_amphipod(types)))),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let burrow = Burrow { rooms, hallway };
+
+        // otherwise the rooms can never be filled
+        for amphipod in 0..types as u8 {
+            if burrow.amphipods().filter(|&other| other == amphipod).count() != depth {
+                return Err(ParseError::new(23, depth + 3, 1, format!("{} amphipods of each type", depth)));
+            }
+        }
+
+        Ok(Input {
+            layout: Layout {
+                hallway: burrow.hallway.len(),
+                entrances,
+                depth,
+                // A: 1, B: 10, C: 100, D: 1000…
+                costs: (0..types as u32).map(|amphipod| 10_u64.pow(amphipod)).collect(),
+            },
+            burrow,
+        })
     }
 }
 
-impl Hash for Input {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H)
-    {
-        self.rooms.hash(state);
-        self.hallway.hash(state);
+// same diagram as in the puzzle
+impl Display for Input {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.layout.draw(f, &self.burrow)
+    }
+}
+
+impl Display for Place {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Place::Hallway(position) => write!(f, "hallway {}", position),
+            Place::Room(room) => write!(f, "room {}", amphipod_name(room as u8)),
+        }
+    }
+}
+
+impl Display for Move {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} from {} to {}, {} energy", self.amphipod, self.from, self.to, self.energy)
     }
 }
 
 #[allow(unused_variables)]
 #[aoc_generator(day23)]
-fn input_generator(input: &str) -> Input {
-    input.parse::<Input>().unwrap()
+fn input_generator(input: &str) -> Result<Input, ParseError> {
+    input.parse::<Input>()
 }
 
 #[allow(unreachable_code)]
 #[test]
 fn part1_provided_exemple_input() {
-    assert_eq!(input_generator(exemple_raw_input()), Input {
-        rooms: [
-            VecDeque::from([1, 10]),
-            VecDeque::from([1000, 100]),
-            VecDeque::from([100, 10]),
-            VecDeque::from([1, 1000]),
-        ],
-        hallway: [0; 11],
-        cost: 
-            // cost of getting all amphipods out
-            10 + 2 + 100 + 1000 * 2 + 10 + 100*2 + 1000 + 2
-            // cost of getting all amphipods in
-             + 3*1111,
+    let input = input_generator(Day23::EXAMPLE).unwrap();
+
+    assert_eq!(input, Input {
+        layout: Layout {
+            hallway: 11,
+            entrances: vec![2, 4, 6, 8],
+            depth: 2,
+            costs: vec![1, 10, 100, 1000],
+        },
+        burrow: Burrow {
+            rooms: vec![vec![0, 1], vec![3, 2], vec![2, 1], vec![0, 3]],
+            hallway: vec![None; 11],
+        },
     });
 
-    assert_eq!(input_generator(exemple_raw_input()).part2(), Input {
-        rooms: [
-            VecDeque::from([1, 1000, 1000, 10]),
-            VecDeque::from([1000, 10, 100, 100]),
-            VecDeque::from([100, 1, 10, 10]),
-            VecDeque::from([1, 100, 1, 1000]),
-        ],
-        hallway: [0; 11],
-        cost: 22193,
-    });
+    assert_eq!(format!("{}", input), Day23::EXAMPLE);
+
+    assert_eq!(input.part2().unwrap().burrow.rooms, vec![
+        vec![0, 3, 3, 1],
+        vec![3, 1, 2, 2],
+        vec![2, 0, 1, 1],
+        vec![0, 2, 0, 3],
+    ]);
+
+    assert_eq!(format!("{}", input.part2().unwrap()), "#############
+#...........#
+###B#C#B#D###
+  #D#C#B#A#
+  #D#B#A#C#
+  #A#D#C#A#
+  #########");
+}
+
+#[test]
+fn input_errors() {
+    assert_eq!(input_generator("#############
+#...........#
+###B#C#E#D###
+  #A#D#C#A#
+  #########"), Err(ParseError::new(23, 3, 8, "an amphipod (A, B, C or D)")));
+
+    assert_eq!(input_generator("#############
+#...........#
+###B#C#B#D###
+  #A#D#C#
+  #########"), Err(ParseError::new(23, 4, 1, "an amphipod in each of the 4 rooms")));
+
+    assert_eq!(input_generator("#############
+#...........#
+###B#C#B#D###
+  #A#D#C#C#
+  #########"), Err(ParseError::new(23, 5, 1, "2 amphipods of each type")));
+
+    assert_eq!(input_generator("#############
+#.....E.....#
+###B#C#.#D###
+  #A#D#C#A#
+  #########"), Err(ParseError::new(23, 2, 7, "an empty space (.) or an amphipod (A, B, C or D)")));
+
+    assert_eq!(input_generator("#############
+#..B........#
+###.#C#B#D###
+  #A#D#C#A#
+  #########"), Err(ParseError::new(23, 2, 4, "an empty space above a room (.)")));
+
+    assert_eq!(input_generator("#######
+#.....#
+##B#C##
+ #A#B#
+ #####"), Err(ParseError::new(23, 3, 5, "an amphipod (A or B)")));
+
+    // the amphipods already in their rooms, side by side
+    let rooms = |types: u8| format!(
+        "{}\n#{}#\n##{}###\n{}",
+        "#".repeat(2 * types as usize + 5),
+        ".".repeat(2 * types as usize + 3),
+        (0..types).map(|amphipod| format!("#{}", amphipod_name(amphipod))).collect::<String>(),
+        "#".repeat(2 * types as usize + 5),
+    );
+
+    assert_eq!(input_generator(&rooms(21)), Err(ParseError::new(23, 3, 44, "at most 20 rooms")));
+    // a T moving across the hallway would already overflow
+    assert_eq!(part1(&input_generator(&rooms(20)).unwrap()), Err(BurrowError::Overflow));
+    assert_eq!(part1(&input_generator(&rooms(10)).unwrap()), Ok(0));
 }
 
-type Output = u32;
+type Output = u64;
 
 #[allow(unused_variables)]
 #[aoc(day23, part1)]
 
-fn part1(input: &Input) -> Output {
-    input.best_solution().cost
+fn part1(input: &Input) -> Result<Output, BurrowError> {
+    input.best_solution().map(|(path, _)| path.cost)
 }
 
-impl Ord for Input {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        other.cost.cmp(&(self.cost))
+impl Layout {
+    // amphipods move at most twice: out of their room and then home, or straight home,
+    // which takes less than twice the steps from the bottom of a room to the end of the hallway
+    fn max_energy(&self) -> Option<u64> {
+        let steps = 2 * (self.hallway + self.depth) as u64;
 
+        self.costs.iter().try_fold(0_u64, |energy, &cost| {
+            energy.checked_add(cost.checked_mul(steps)?.checked_mul(self.depth as u64)?)
+        })
     }
-}
 
-impl PartialOrd for Input {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
+    fn draw(&self, f: &mut std::fmt::Formatter<'_>, burrow: &Burrow) -> std::fmt::Result {
+        let width = self.hallway + 2;
+        // the walls around the rooms below the first row
+        let (left, right) = (self.entrances[0], self.entrances[self.entrances.len() - 1] + 2);
+
+        writeln!(f, "{}", "#".repeat(width))?;
+        writeln!(f, "#{}#", burrow.hallway.iter().map(|amphipod| amphipod.map_or('.', amphipod_name)).collect::<String>())?;
+
+        for row in (0..self.depth).rev() {
+            let (start, end) = if row == self.depth - 1 { (0, width) } else { (left, right + 1) };
+
+            let line = (0..end)
+                .map(|column| match self.entrances.iter().position(|&entrance| entrance + 1 == column) {
+                    Some(room) => burrow.rooms[room].get(row).map_or('.', |&amphipod| amphipod_name(amphipod)),
+                    None if column < start => ' ',
+                    None => '#',
+                })
+                .collect::<String>();
+
+            writeln!(f, "{}", line)?;
+        }
+
+        write!(f, "{}{}", " ".repeat(left), "#".repeat(right + 1 - left))
     }
 }
 
 impl Input {
-    pub fn from_vec(positions: Vec<(usize, u32)>) -> Self {
-        let mut input = Self::default();
-        for (queue, value) in positions {
-            input.push(queue, value);
-        }   
-        
-        for n in 1..=input.rooms[2].len() {
-            input.cost += n as u32 * 1111;
+    // energy per step of each amphipod type, from A onwards
+    pub fn with_costs(mut self, costs: Vec<u64>) -> Result<Self, BurrowError> {
+        if costs.len() != self.layout.costs.len() {
+            return Err(BurrowError::Costs { types: self.layout.costs.len(), costs: costs.len() });
+        }
+
+        self.layout.costs = costs;
+
+        Ok(self)
+    }
+
+    // adds `rows` (one amphipod per room, eg: "DCBA") to the rooms,
+    // `at` counts the rows from the top
+    fn insert_rows(&self, at: usize, rows: &[&str]) -> Result<Self, BurrowError> {
+        let mut input = self.clone();
+        let types = self.layout.entrances.len();
+
+        for row in rows {
+            let amphipods = row.chars().map(|c| amphipod(c, types)).collect::<Option<Vec<_>>>();
+
+            if amphipods.is_none_or(|amphipods| amphipods.len() != types) {
+                return Err(BurrowError::Unfold { rooms: types, row: row.to_string() });
+            }
         }
 
-        input
+        for (room, amphipods) in input.burrow.rooms.iter_mut().enumerate() {
+            amphipods.reverse();
+
+            for (n, row) in rows.iter().enumerate() {
+                let c = row.chars().nth(room).unwrap();
+
+                amphipods.insert(at + n, amphipod(c, types).unwrap());
+            }
+
+            amphipods.reverse();
+        }
+
+        input.layout.depth += rows.len();
+
+        Ok(input)
     }
-    
-    fn push(&mut self, room: usize, value: u32) {
-        self.rooms[room].push_front(value);
 
-        self.cost += self.rooms[room].len() as u32 * value;
+    // the unfolded rows are made for the 4 rooms of the puzzle
+    fn part2(&self) -> Result<Self, BurrowError> {
+        self.insert_rows(1, &["DCBA", "DBAC"])
     }
 
-    fn part2(&self) -> Self {
-        let positions = 
-            self.rooms.iter().map(|v| v[1]).enumerate()
-            .chain([1000, 100, 10, 1].iter().cloned().enumerate())
-            .chain([1000, 10, 1, 100].iter().cloned().enumerate())
-            .chain(self.rooms.iter().map(|v| v[0]).enumerate())
-            .collect::<Vec<_>>();
+    // with the moves leading from each state of the path to the next one
+    fn best_solution(&self) -> Result<(Path<Burrow, u64>, Vec<Move>), BurrowError> {
+        self.layout.max_energy().ok_or(BurrowError::Overflow)?;
 
-        Self::from_vec(positions)
+        search::dijkstra_labeled(
+            self.burrow.clone(),
+            |burrow| burrow.moves(&self.layout).into_iter().map(|(next, m)| (next, m, m.energy)),
+            |burrow| burrow.solved(&self.layout),
+        ).ok_or(BurrowError::Deadlock)
     }
 
-    fn solved(&self) -> bool {
-        self.rooms.iter().all(|slot| slot.is_empty()) && self.hallway.iter().all(|&v| v == 0)
+    // the moves of the best solution, with the burrow after each of them
+    fn steps(&self) -> Result<Vec<(Move, Burrow)>, BurrowError> {
+        let (path, moves) = self.best_solution()?;
+
+        Ok(moves.into_iter().zip(path.states.into_iter().skip(1)).collect())
     }
 
-    fn move_cost(&self, start: usize) -> u32 {
-        match start {
-            2|4|6|8 => {
-                let room = (start / 2) - 1;
+    pub fn moves(&self) -> Result<Vec<Move>, BurrowError> {
+        Ok(self.steps()?.into_iter().map(|(m, _)| m).collect())
+    }
 
-                *self.rooms[room].iter().rev().next().unwrap_or(&0)
-            }
-            _ => self.hallway[start],
+    // the diagram of the burrow before the first move and after each move
+    pub fn replay(&self) -> Result<String, BurrowError> {
+        let mut replay = self.to_string();
+
+        for (m, burrow) in self.steps()? {
+            let input = Input { layout: self.layout.clone(), burrow };
+
+            replay.push_str(&format!("\n\n{}\n{}", m, input));
         }
+
+        Ok(replay)
     }
+}
 
-    fn move_options(&self, start: usize, move_cost: u32) -> Vec<(usize, u32)> {
-        let mut options = vec![];
-
-        if move_cost != 0 {
-            let left = (0..start).rev().take_while(|&end| self.hallway[end] == 0);
-            let right = (start+1..11).take_while(|&end| self.hallway[end] == 0);
-    
-            for end in left.chain(right) {
-                match end {
-                    2|4|6|8 => {
-                        let room = (end / 2) - 1;
-    
-                        if !self.rooms[room].is_empty() || 10_u32.pow(room as u32) != move_cost {
-                            continue;
-                        }
-    
-                        return vec![(end, move_cost)];
-                    }, 
-                    _ => match start {
-                        2|4|6|8 => options.push((end, move_cost)),
-                        _ => {},
-                    },
-                }
-            }
-        }
+impl Burrow {
+    fn amphipods(&self) -> impl Iterator<Item = u8> + '_ {
+        self.rooms.iter().flatten().cloned().chain(self.hallway.iter().flatten().cloned())
+    }
 
-        options
+    fn solved(&self, layout: &Layout) -> bool {
+        (0..self.rooms.len()).all(|room| self.rooms[room].len() == layout.depth && self.accepts(room))
     }
 
-    fn moves(&self, blacklist: &mut HashMap<Self, ()>) -> BinaryHeap<Self> {
-        let mut moves = BinaryHeap::new();
-        
-        for start in 0..11 {
-            let move_cost = self.move_cost(start);
-
-            for (end, move_cost) in self.move_options(start, move_cost) {
-                blacklist.entry(self.move_to(start, end, move_cost))
-                    .or_insert_with_key(|key| {
-                        moves.push(key.clone());
-                    });
-            }
-        }
+    // amphipods only go into their room once there are no other types left in it,
+    // and the ones already there never leave
+    fn accepts(&self, room: usize) -> bool {
+        self.rooms[room].iter().all(|&amphipod| amphipod as usize == room)
+    }
 
-        moves
+    // whether nothing is in the way in the hallway after `from` up to `to`
+    fn clear(&self, from: usize, to: usize) -> bool {
+        if from <= to {
+            (from + 1..=to).all(|position| self.hallway[position].is_none())
+        } else {
+            (to..from).all(|position| self.hallway[position].is_none())
+        }
     }
 
-    fn move_to(&self, start: usize, end: usize, move_cost: u32) -> Self {
-        let moves = (std::cmp::max(start, end) - std::cmp::min(start, end)) as u32;
-        assert_ne!(moves, 0);
-        assert_ne!(move_cost, 0);
-        let mut rooms = self.rooms.clone();
-        let mut hallway = self.hallway;
+    // steps between the hallway and the first free space of `room`
+    fn room_steps(&self, layout: &Layout, room: usize) -> usize {
+        layout.depth - self.rooms[room].len()
+    }
 
-        match start {
-            2|4|6|8 => {
-                rooms[(start / 2) - 1].pop_back();
-            },
-            _ => {
-                hallway[start] = 0;
-            },
+    fn moves(&self, layout: &Layout) -> Vec<(Self, Move)> {
+        let step = |amphipod: u8, from, to, steps: usize| Move {
+            amphipod: amphipod_name(amphipod),
+            from,
+            to,
+            energy: steps as u64 * layout.costs[amphipod as usize],
         };
 
-        match end {
-            2|4|6|8 => {},
-            _ => hallway[end] = move_cost,
-        }
+        // going home is never a bad idea, no need to look at the other moves
+        for (position, amphipod) in self.hallway.iter().enumerate() {
+            if let &Some(amphipod) = amphipod {
+                let home = amphipod as usize;
+                let entrance = layout.entrances[home];
+
+                if self.accepts(home) && self.clear(position, entrance) {
+                    let mut next = self.clone();
 
-        Self {
-            hallway,
-            rooms,
-            cost: self.cost + move_cost * moves,
+                    next.hallway[position] = None;
+                    next.rooms[home].push(amphipod);
+
+                    let steps = position.abs_diff(entrance) + self.room_steps(layout, home);
+
+                    return vec![(next, step(amphipod, Place::Hallway(position), Place::Room(home), steps))];
+                }
+            }
         }
-    }
 
-    fn best_solution(&self) -> Self {
-        let mut blacklist: HashMap<Self, ()> = HashMap::new();
-        let mut moves = BinaryHeap::from([self.clone()]);
-        
-        let mut best_solution = Self::default();
-        best_solution.cost = u32::MAX;
+        let mut moves = vec![];
 
-        while let Some(input) = moves.pop() {
-            if input.cost > best_solution.cost {
+        for (room, &entrance) in layout.entrances.iter().enumerate() {
+            if self.accepts(room) {
                 continue;
             }
 
-            if input.solved() {
-                best_solution = input;
+            let amphipod = *self.rooms[room].last().unwrap();
+            let home = amphipod as usize;
+            let exit = self.room_steps(layout, room) + 1;
 
-                continue;
+            // straight from one room to the other
+            if self.accepts(home) && self.clear(entrance, layout.entrances[home]) {
+                let mut next = self.clone();
+
+                next.rooms[room].pop();
+                next.rooms[home].push(amphipod);
+
+                let steps = exit + entrance.abs_diff(layout.entrances[home]) + self.room_steps(layout, home);
+
+                return vec![(next, step(amphipod, Place::Room(room), Place::Room(home), steps))];
             }
 
-            moves.append(&mut input.moves(&mut blacklist));
+            for position in 0..layout.hallway {
+                if layout.entrances.contains(&position) || !self.clear(entrance, position) {
+                    continue;
+                }
+
+                let mut next = self.clone();
+
+                next.rooms[room].pop();
+                next.hallway[position] = Some(amphipod);
+
+                moves.push((next, step(amphipod, Place::Room(room), Place::Hallway(position), exit + entrance.abs_diff(position))));
+            }
         }
 
-        best_solution
+        moves
     }
 }
 
+#[cfg(test)]
+fn parse_burrow(diagram: &str) -> (Layout, Burrow) {
+    let input = input_generator(diagram).unwrap();
+
+    (input.layout, input.burrow)
+}
+
 #[test]
 fn test_solved() {
-    assert_eq!(Input {
-        ..Default::default()
-    }.solved(), true);
-
-    assert_eq!(Input {
-        hallway: [1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
-        ..Default::default()
-    }.solved(), false);
-
-    assert_eq!(Input {
-        rooms: [
-            VecDeque::from([10]),
-            VecDeque::from([]),
-            VecDeque::from([]),
-            VecDeque::from([]),
-        ],
-        ..Default::default()
-    }.solved(), false);
+    let (layout, burrow) = parse_burrow("#############
+#...........#
+###A#B#C#D###
+  #A#B#C#D#
+  #########");
+
+    assert!(burrow.solved(&layout));
+
+    let (layout, burrow) = parse_burrow("#############
+#A..........#
+###.#B#C#D###
+  #A#B#C#D#
+  #########");
+
+    assert!(!burrow.solved(&layout));
 }
 
 #[test]
 fn test_input_moves() {
-    assert_eq!(Input {
-        ..Default::default()
-    }.moves(&mut HashMap::new()).iter().cloned().collect::<Vec<Input>>(), vec![]);
-
-    assert_eq!(Input {
-        hallway: [1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
-        rooms: [
-            VecDeque::from([]),
-            VecDeque::from([]),
-            VecDeque::from([]),
-            VecDeque::from([]),
-        ],
-        ..Default::default()
-    }.moves(&mut HashMap::new()).len(), 1);
-
-    assert_eq!(Input {
-        hallway: [1, 0, 0, 10, 0, 0, 0, 0, 0, 0, 0],
-        rooms: [
-            VecDeque::from([]),
-            VecDeque::from([]),
-            VecDeque::from([]),
-            VecDeque::from([]),
-        ],
-        ..Default::default()
-    }.moves(&mut HashMap::new()).len(), 2);
-
-    assert_eq!(Input {
-        hallway: [0, 0, 0, 100, 0, 1, 0, 0, 0, 0, 0],
-        rooms: [
-            VecDeque::from([]),
-            VecDeque::from([]),
-            VecDeque::from([]),
-            VecDeque::from([]),
-        ],
-        ..Default::default()
-    }.moves(&mut HashMap::new()).len(), 0);
-
-    assert_eq!(Input {
-        rooms: [
-            VecDeque::from([10]),
-            VecDeque::from([]),
-            VecDeque::from([]),
-            VecDeque::from([]),
-        ],
-        ..Default::default()
-    }.moves(&mut HashMap::new()).len(), 1);
-
-    assert_eq!(Input {
-        rooms: [
-            VecDeque::from([10, 100]),
-            VecDeque::from([]),
-            VecDeque::from([]),
-            VecDeque::from([]),
-        ],
-        ..Default::default()
-    }.moves(&mut HashMap::new()).pop().unwrap().cost, 400);
+    // the A goes home
+    let (layout, burrow) = parse_burrow("#############
+#.........A.#
+###.#B#C#D###
+  #A#B#C#D#
+  #########");
+
+    assert_eq!(burrow.moves(&layout).iter().map(|(_, m)| m.energy).collect::<Vec<_>>(), [8]);
+
+    // the B goes straight from the first room to its own
+    let (layout, burrow) = parse_burrow("#############
+#A..........#
+###B#.#C#D###
+  #A#B#C#D#
+  #########");
+
+    assert_eq!(burrow.moves(&layout).iter().map(|(_, m)| m.energy).collect::<Vec<_>>(), [40]);
+
+    // the B and the A can each stop at the 7 places of the hallway
+    let (layout, burrow) = parse_burrow("#############
+#...........#
+###B#A#C#D###
+  #A#B#C#D#
+  #########");
+
+    assert_eq!(burrow.moves(&layout).len(), 14);
+
+    // but not past another amphipod
+    let (layout, burrow) = parse_burrow("#############
+#...B.......#
+###.#A#C#D###
+  #A#B#C#D#
+  #########");
+
+    assert_eq!(burrow.moves(&layout).len(), 4);
 }
 
 #[allow(unreachable_code)]
 #[test]
 fn part1_provided_exemple() {
-    assert_eq!(part1(&Input {
-        hallway: [0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0],
-        rooms: [
-            VecDeque::from([]),
-            VecDeque::from([]),
-            VecDeque::from([]),
-            VecDeque::from([]),
-        ],
-        ..Default::default()
-    }), 7);
-
-    assert_eq!(part1(&Input {
-        hallway: [0, 0, 0, 0, 0, 1000, 0, 1000, 0, 1, 0],
-        rooms: [
-            VecDeque::from([]),
-            VecDeque::from([]),
-            VecDeque::from([]),
-            VecDeque::from([]),
-        ],
-        ..Default::default()
-    }), 4007);
-
-    assert_eq!(part1(&Input {
-        hallway: [0, 0, 0, 0, 0, 1000, 0, 0, 0, 0, 0],
-        rooms: [
-            VecDeque::from([]),
-            VecDeque::from([]),
-            VecDeque::from([]),
-            VecDeque::from([1, 1000]),
-        ],
-        ..Default::default()
-    }), 5008);
-
-    assert_eq!(part1(&input_generator(exemple_raw_input())), 12521);
+    assert_eq!(part1(&input_generator("#############
+#.........A.#
+###.#B#C#D###
+  #A#B#C#D#
+  #########").unwrap()), Ok(8));
+
+    assert_eq!(part1(&input_generator("#############
+#.....D.D.A.#
+###.#B#C#.###
+  #A#B#C#.#
+  #########").unwrap()), Ok(7008));
+
+    assert_eq!(part1(&input_generator(Day23::EXAMPLE).unwrap()), Ok(12521));
+}
+
+#[test]
+fn other_layouts() {
+    let input = input_generator("#######
+#.....#
+##B#A##
+ #A#B#
+ #####").unwrap();
+
+    assert_eq!(format!("{}", input), "#######
+#.....#
+##B#A##
+ #A#B#
+ #####");
+
+    // the A steps aside to let the B go straight home
+    assert_eq!(part1(&input), Ok(46));
+    // unless the As are the expensive ones
+    assert_eq!(part1(&input.clone().with_costs(vec![100, 1]).unwrap()), Ok(406));
+    assert_eq!(input.clone().with_costs(vec![1, 10, 100]), Err(BurrowError::Costs { types: 2, costs: 3 }));
+    assert_eq!(part1(&input.with_costs(vec![u64::MAX / 8, 1]).unwrap()), Err(BurrowError::Overflow));
+
+    let input = input_generator("###########
+#.........#
+###C#B#A###
+  #A#B#C#
+  #A#B#C#
+  #######").unwrap();
+
+    assert_eq!((input.layout.hallway, input.layout.depth), (9, 3));
+    assert_eq!(input.layout.entrances, [2, 4, 6]);
+    // the A steps aside rather than the C, which would be in the way of the A
+    assert_eq!(part1(&input), Ok(608));
+}
+
+#[test]
+fn unsolvable() {
+    // the D and the A are in each other's way, and the D in room C can't get out
+    let input = input_generator("#############
+#.....D.A...#
+###B#C#D#.###
+  #A#B#C#.#
+  #########").unwrap();
+
+    assert_eq!(part1(&input), Err(BurrowError::Deadlock));
+    assert_eq!(input.replay(), Err(BurrowError::Deadlock));
+
+    let input = input_generator("#######
+#.....#
+##B#A##
+ #A#B#
+ #####").unwrap();
+
+    assert_eq!(part2(&input), Err(BurrowError::Unfold { rooms: 2, row: "DCBA".to_string() }));
+
+    assert_eq!(
+        crate::solve(23, 1, "#############\n#.....D.A...#\n###B#C#D#.###\n  #A#B#C#.#\n  #########"),
+        Err(crate::SolveError::Failed { day: 23, part: 1, reason: "the amphipods can't all get to their rooms".to_string() }),
+    );
+    assert_eq!(
+        crate::solve(23, 2, "#######\n#.....#\n##B#A##\n #A#B#\n #####").unwrap_err().to_string(),
+        "day 23 part 2: can't unfold the row DCBA in 2 rooms",
+    );
+}
+
+#[test]
+fn replay() {
+    let input = input_generator("#######
+#.....#
+##B#A##
+ #A#B#
+ #####").unwrap();
+
+    assert_eq!(input.moves().unwrap(), [
+        Move { amphipod: 'A', from: Place::Room(1), to: Place::Hallway(4), energy: 2 },
+        Move { amphipod: 'B', from: Place::Room(0), to: Place::Room(1), energy: 40 },
+        Move { amphipod: 'A', from: Place::Hallway(4), to: Place::Room(0), energy: 4 },
+    ]);
+
+    assert_eq!(input.replay().unwrap(), "#######
+#.....#
+##B#A##
+ #A#B#
+ #####
+
+A from room B to hallway 4, 2 energy
+#######
+#....A#
+##B#.##
+ #A#B#
+ #####
+
+B from room A to room B, 40 energy
+#######
+#....A#
+##.#B##
+ #A#B#
+ #####
+
+A from hallway 4 to room A, 4 energy
+#######
+#.....#
+##A#B##
+ #A#B#
+ #####");
+
+    // the same moves as in the puzzle
+    let input = input_generator(Day23::EXAMPLE).unwrap();
+    let room = Place::Room;
+    let hallway = Place::Hallway;
+
+    assert_eq!(input.moves().unwrap(), [
+        Move { amphipod: 'B', from: room(2), to: hallway(3), energy: 40 },
+        Move { amphipod: 'C', from: room(1), to: room(2), energy: 400 },
+        Move { amphipod: 'D', from: room(1), to: hallway(5), energy: 3000 },
+        Move { amphipod: 'B', from: hallway(3), to: room(1), energy: 30 },
+        Move { amphipod: 'B', from: room(0), to: room(1), energy: 40 },
+        Move { amphipod: 'D', from: room(3), to: hallway(7), energy: 2000 },
+        Move { amphipod: 'A', from: room(3), to: hallway(9), energy: 3 },
+        Move { amphipod: 'D', from: hallway(7), to: room(3), energy: 3000 },
+        Move { amphipod: 'D', from: hallway(5), to: room(3), energy: 4000 },
+        Move { amphipod: 'A', from: hallway(9), to: room(0), energy: 8 },
+    ]);
+
+    assert_eq!(input.replay().unwrap(), "#############
+#...........#
+###B#C#B#D###
+  #A#D#C#A#
+  #########
+
+B from room C to hallway 3, 40 energy
+#############
+#...B.......#
+###B#C#.#D###
+  #A#D#C#A#
+  #########
+
+C from room B to room C, 400 energy
+#############
+#...B.......#
+###B#.#C#D###
+  #A#D#C#A#
+  #########
+
+D from room B to hallway 5, 3000 energy
+#############
+#...B.D.....#
+###B#.#C#D###
+  #A#.#C#A#
+  #########
+
+B from hallway 3 to room B, 30 energy
+#############
+#.....D.....#
+###B#.#C#D###
+  #A#B#C#A#
+  #########
+
+B from room A to room B, 40 energy
+#############
+#.....D.....#
+###.#B#C#D###
+  #A#B#C#A#
+  #########
+
+D from room D to hallway 7, 2000 energy
+#############
+#.....D.D...#
+###.#B#C#.###
+  #A#B#C#A#
+  #########
+
+A from room D to hallway 9, 3 energy
+#############
+#.....D.D.A.#
+###.#B#C#.###
+  #A#B#C#.#
+  #########
+
+D from hallway 7 to room D, 3000 energy
+#############
+#.....D...A.#
+###.#B#C#.###
+  #A#B#C#D#
+  #########
+
+D from hallway 5 to room D, 4000 energy
+#############
+#.........A.#
+###.#B#C#D###
+  #A#B#C#D#
+  #########
+
+A from hallway 9 to room A, 8 energy
+#############
+#...........#
+###A#B#C#D###
+  #A#B#C#D#
+  #########");
 }
 
 #[allow(unused_variables)]
 #[aoc(day23, part2)]
 
-fn part2(input: &Input) -> Output {
-    input.part2().best_solution().cost
+fn part2(input: &Input) -> Result<Output, BurrowError> {
+    input.part2()?.best_solution().map(|(path, _)| path.cost)
 }
 
 #[allow(unreachable_code)]
 #[test]
 fn part2_provided_exemple() {
-    assert_eq!(part2(&input_generator(exemple_raw_input())), 44169);
+    assert_eq!(part2(&input_generator(Day23::EXAMPLE).unwrap()), Ok(44169));
 }
 
-#[allow(unreachable_code)]
-#[allow(dead_code)]
-fn exemple_raw_input() -> &'static str {
-    "#############
+pub struct Day23;
+
+impl Puzzle for Day23 {
+    const DAY: u8 = 23;
+    const EXAMPLE: &'static str = "#############
 #...........#
 ###B#C#B#D###
   #A#D#C#A#
-  #########"
+  #########";
+    const EXAMPLE_ANSWERS: [Option<Answer>; 2] = [Some(Answer::Number(12521)), Some(Answer::Number(44169))];
+
+    type Input = Input;
+    type Output1 = Result<Output, BurrowError>;
+    type Output2 = Result<Output, BurrowError>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
 }
@@ -1,9 +1,11 @@
-use std::{collections::HashSet, hash::Hash, sync::Arc};
+use std::{borrow::Cow, collections::HashSet, error::Error, hash::Hash, ops::Range, str::FromStr, sync::Arc};
 
 use parse_display::{Display, FromStr};
 
-#[derive(Debug, PartialEq)]
-struct Input {
+use crate::{parse::{self, ParseError}, puzzle::Puzzle, solve::Answer};
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Input {
     instructions: Vec<Instruction>,
 }
 
@@ -19,7 +21,7 @@ enum Var {
     Z,
 }
 
-#[derive(Display, FromStr, PartialEq, Eq, Hash, Debug, Clone)]
+#[derive(Display, FromStr, PartialEq, Eq, Hash, Debug, Clone, Copy)]
 enum Val {
     #[display("{0}")]
     Literal(i32),
@@ -27,7 +29,7 @@ enum Val {
     Var(Var),
 }
 
-#[derive(Display, FromStr, PartialEq, Eq, Hash, Debug, Clone)]
+#[derive(Display, PartialEq, Eq, Hash, Debug, Clone)]
 enum Instruction {
     #[display("inp {0}")]
     Inp(Var),
@@ -41,29 +43,73 @@ enum Instruction {
     Mod(Var, Val),
     #[display("eql {0} {1}")]
     Eql(Var, Val),
+    // not part of the ALU, only the optimizer produces these
+    #[display("set {0} {1}")]
+    Set(Var, Val),
+    #[display("neq {0} {1}")]
+    Neq(Var, Val),
+}
+
+// the instructions a program can be made of, without the ones of the optimizer
+#[derive(FromStr)]
+enum AluInstruction {
+    #[display("inp {0}")]
+    Inp(Var),
+    #[display("add {0} {1}")]
+    Add(Var, Val),
+    #[display("mul {0} {1}")]
+    Mul(Var, Val),
+    #[display("div {0} {1}")]
+    Div(Var, Val),
+    #[display("mod {0} {1}")]
+    Mod(Var, Val),
+    #[display("eql {0} {1}")]
+    Eql(Var, Val),
+}
+
+impl FromStr for Instruction {
+    type Err = parse_display::ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.parse()? {
+            AluInstruction::Inp(a) => Instruction::Inp(a),
+            AluInstruction::Add(a, b) => Instruction::Add(a, b),
+            AluInstruction::Mul(a, b) => Instruction::Mul(a, b),
+            AluInstruction::Div(a, b) => Instruction::Div(a, b),
+            AluInstruction::Mod(a, b) => Instruction::Mod(a, b),
+            AluInstruction::Eql(a, b) => Instruction::Eql(a, b),
+        })
+    }
 }
 
 #[allow(unused_variables)]
 #[aoc_generator(day24)]
-fn input_generator(input: &str) -> Input {
-    Input {
-        instructions: input.lines()
-            .map(|line| line.parse::<Instruction>().unwrap())
-            .collect::<Vec<_>>(),
-    }
+fn input_generator(input: &str) -> Result<Input, ParseError> {
+    Ok(Input {
+        instructions: parse::lines(input)
+            .map(|line| parse::parse(24, line, line.1, "an ALU instruction"))
+            .collect::<Result<Vec<_>, _>>()?,
+    })
 }
 
 #[test]
 fn test_parse_input() {
     assert_eq!(input_generator("inp w
 add z w
-mod w -12"), Input {
+mod w -12"), Ok(Input {
         instructions: vec![
             Instruction::Inp(Var::W),
             Instruction::Add(Var::Z, Val::Var(Var::W)),
             Instruction::Mod(Var::W, Val::Literal(-12)),
         ],
-    });
+    }));
+
+    assert_eq!(input_generator("inp w
+sub z w"), Err(ParseError::new(24, 2, 1, "an ALU instruction")));
+
+    assert_eq!(input_generator("inp w
+set z w"), Err(ParseError::new(24, 2, 1, "an ALU instruction")));
+    assert!("neq z w".parse::<Instruction>().is_err());
 }
 
 impl Val {
@@ -76,6 +122,15 @@ impl Val {
 }
 
 impl Var {
+    fn index(&self) -> usize {
+        match self {
+            Var::W => 0,
+            Var::X => 1,
+            Var::Y => 2,
+            Var::Z => 3,
+        }
+    }
+
     pub fn get(&self, mem: &[i32; 4]) -> i32 {
         match self {
             Var::W => mem[0],
@@ -95,32 +150,143 @@ impl Var {
     }
 }
 
-impl Instruction {
-    pub fn exec(&self, mem: &mut [i32; 4], input: &mut dyn Iterator<Item=&i32>) {
+// the ALU crashes instead of running these, the instruction isn't executed
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum AluFault {
+    // `div` or `mod` by 0
+    DivisionByZero,
+    NegativeModulo,
+    // the result doesn't fit in a register
+    Overflow,
+    NoInput,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct AluError {
+    // of the faulty instruction, counted from 0
+    pub index: usize,
+    pub fault: AluFault,
+}
+
+impl std::fmt::Display for AluFault {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Instruction::Inp(a) => a.set(mem, *input.next().expect("no more input")),
-            Instruction::Add(a, b) => {
-                let v = a.get(&mem) + b.get(&mem);
-                a.set(mem, v);
-            },
-            Instruction::Mul(a, b) => {
-                let v = a.get(&mem) * b.get(&mem);
-                a.set(mem, v);
-            },
-            Instruction::Div(a, b) => {
-                let v = a.get(&mem) / b.get(&mem);
-                a.set(mem, v);
-            },
-            Instruction::Mod(a, b) => {
-                let v = a.get(&mem) % b.get(&mem);
-                a.set(mem, v);
+            AluFault::DivisionByZero => write!(f, "division by zero"),
+            AluFault::NegativeModulo => write!(f, "modulo with a negative operand"),
+            AluFault::Overflow => write!(f, "overflow"),
+            AluFault::NoInput => write!(f, "no more input"),
+        }
+    }
+}
+
+impl std::fmt::Display for AluError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "instruction {}: {}", self.index, self.fault)
+    }
+}
+
+impl Error for AluError {}
+
+impl Instruction {
+    // an instruction doesn't know where it is in its program, `Program::next` adds the index
+    pub fn exec(&self, mem: &mut [i32; 4], input: &mut dyn Iterator<Item=&i32>) -> Result<(), AluFault> {
+        let (a, v) = match self {
+            Instruction::Inp(a) => (a, *input.next().ok_or(AluFault::NoInput)?),
+            Instruction::Add(a, b) => (a, a.get(mem).checked_add(b.get(mem)).ok_or(AluFault::Overflow)?),
+            Instruction::Mul(a, b) => (a, a.get(mem).checked_mul(b.get(mem)).ok_or(AluFault::Overflow)?),
+            Instruction::Div(a, b) => match b.get(mem) {
+                0 => return Err(AluFault::DivisionByZero),
+                // i32::MIN / -1 doesn't fit
+                b => (a, a.get(mem).checked_div(b).ok_or(AluFault::Overflow)?),
             },
-            Instruction::Eql(a, b) => {
-                let v = a.get(&mem) == b.get(&mem);
-                a.set(mem, v as i32);
+            Instruction::Mod(a, b) => match (a.get(mem), b.get(mem)) {
+                (_, 0) => return Err(AluFault::DivisionByZero),
+                (x, y) if x < 0 || y < 0 => return Err(AluFault::NegativeModulo),
+                (x, y) => (a, x % y),
             },
+            Instruction::Eql(a, b) => (a, (a.get(mem) == b.get(mem)) as i32),
+            Instruction::Set(a, b) => (a, b.get(mem)),
+            Instruction::Neq(a, b) => (a, (a.get(mem) != b.get(mem)) as i32),
+        };
+
+        a.set(mem, v);
+
+        Ok(())
+    }
+
+    // the register written by the instruction
+    fn target(&self) -> Var {
+        match self {
+            Instruction::Inp(a) |
+            Instruction::Add(a, _) |
+            Instruction::Mul(a, _) |
+            Instruction::Div(a, _) |
+            Instruction::Mod(a, _) |
+            Instruction::Eql(a, _) |
+            Instruction::Set(a, _) |
+            Instruction::Neq(a, _) => *a,
+        }
+    }
+
+    fn operand(&self) -> Option<Val> {
+        match self {
+            Instruction::Inp(_) => None,
+            Instruction::Add(_, b) |
+            Instruction::Mul(_, b) |
+            Instruction::Div(_, b) |
+            Instruction::Mod(_, b) |
+            Instruction::Eql(_, b) |
+            Instruction::Set(_, b) |
+            Instruction::Neq(_, b) => Some(*b),
         }
     }
+
+    fn with_operand(&self, b: Val) -> Self {
+        match *self {
+            Instruction::Inp(a) => Instruction::Inp(a),
+            Instruction::Add(a, _) => Instruction::Add(a, b),
+            Instruction::Mul(a, _) => Instruction::Mul(a, b),
+            Instruction::Div(a, _) => Instruction::Div(a, b),
+            Instruction::Mod(a, _) => Instruction::Mod(a, b),
+            Instruction::Eql(a, _) => Instruction::Eql(a, b),
+            Instruction::Set(a, _) => Instruction::Set(a, b),
+            Instruction::Neq(a, _) => Instruction::Neq(a, b),
+        }
+    }
+
+    // whether the result depends on the previous value of the target
+    fn reads_target(&self) -> bool {
+        !matches!(self, Instruction::Inp(_) | Instruction::Set(..))
+    }
+
+    // whether it can crash the ALU or consume input, those can't be removed
+    fn has_effects(&self) -> bool {
+        !matches!(self, Instruction::Eql(..) | Instruction::Set(..) | Instruction::Neq(..))
+    }
+}
+
+#[test]
+fn test_exec_errors() {
+    let exec = |instruction: &str, mem: [i32; 4], input: &[i32]| {
+        let mut mem = mem;
+
+        instruction.parse::<Instruction>().unwrap()
+            .exec(&mut mem, &mut input.iter())
+            .map(|_| mem)
+    };
+
+    assert_eq!(exec("div x y", [0, 7, 2, 0], &[]), Ok([0, 3, 2, 0]));
+    assert_eq!(exec("div x y", [0, -7, 2, 0], &[]), Ok([0, -3, 2, 0]));
+    assert_eq!(exec("div x 0", [0, 7, 0, 0], &[]), Err(AluFault::DivisionByZero));
+    assert_eq!(exec("div x -1", [0, i32::MIN, 0, 0], &[]), Err(AluFault::Overflow));
+    assert_eq!(exec("mod x y", [0, 7, 2, 0], &[]), Ok([0, 1, 2, 0]));
+    assert_eq!(exec("mod x y", [0, 7, 0, 0], &[]), Err(AluFault::DivisionByZero));
+    assert_eq!(exec("mod x y", [0, -7, 2, 0], &[]), Err(AluFault::NegativeModulo));
+    assert_eq!(exec("mod x -2", [0, 7, 0, 0], &[]), Err(AluFault::NegativeModulo));
+    assert_eq!(exec("add z 1", [0, 0, 0, i32::MAX], &[]), Err(AluFault::Overflow));
+    assert_eq!(exec("mul z 2", [0, 0, 0, i32::MIN], &[]), Err(AluFault::Overflow));
+    assert_eq!(exec("inp w", [0; 4], &[4]), Ok([4, 0, 0, 0]));
+    assert_eq!(exec("inp w", [0; 4], &[]), Err(AluFault::NoInput));
 }
 
 #[derive(Debug, PartialEq, Clone, Eq, Hash)]
@@ -139,23 +305,25 @@ impl Program {
         }
     }
 
-    pub fn next(&self, input: &mut dyn Iterator<Item=&i32>) -> Program {
+    pub fn next(&self, input: &mut dyn Iterator<Item=&i32>) -> Result<Program, AluError> {
         let mut mem = self.mem.clone();
 
-        self.instructions[self.next_instruction].exec(&mut mem, input);
+        self.instructions[self.next_instruction]
+            .exec(&mut mem, input)
+            .map_err(|fault| AluError { index: self.next_instruction, fault })?;
 
-        Program {
+        Ok(Program {
             next_instruction: self.next_instruction + 1,
             instructions: self.instructions.clone(),
             mem,
-        }
+        })
     }
 
-    pub fn advance_unless_input(&self, input: &mut dyn Iterator<Item=&i32>) -> Program {
+    pub fn advance_unless_input(&self, input: &mut dyn Iterator<Item=&i32>) -> Result<Program, AluError> {
         match self.instructions.get(self.next_instruction) {
-            Some(Instruction::Inp(_)) | None => self.clone(),
+            Some(Instruction::Inp(_)) | None => Ok(self.clone()),
             _ => {
-                let p = self.next(input);
+                let p = self.next(input)?;
                 
                 p.advance_unless_input(input)
             },
@@ -175,11 +343,15 @@ impl Program {
                     Instruction::Div(a, _) |
                     Instruction::Mod(a, _) |
                     Instruction::Eql(a, _) |
+                    Instruction::Set(a, _) |
+                    Instruction::Neq(a, _) |
                     Instruction::Add(_, Val::Var(a)) |
                     Instruction::Mul(_, Val::Var(a)) |
                     Instruction::Div(_, Val::Var(a)) |
                     Instruction::Mod(_, Val::Var(a)) |
-                    Instruction::Eql(_, Val::Var(a)) if a == v => {},
+                    Instruction::Eql(_, Val::Var(a)) |
+                    Instruction::Set(_, Val::Var(a)) |
+                    Instruction::Neq(_, Val::Var(a)) if a == v => {},
                     _ => {
                         instructions.swap(i, i + 1);
                     },
@@ -245,29 +417,1012 @@ fn test_program() {
 
     let mut input = [5, 3].iter();
 
-    let program = program.next(&mut input);
+    let program = program.next(&mut input).unwrap();
 
     assert_eq!(program.next_instruction, 1);
     assert_eq!(program.mem, [0, 5, 0, 0]);
 
-    let program = program.next(&mut input);
+    let program = program.next(&mut input).unwrap();
 
     assert_eq!(program.next_instruction, 2);
     assert_eq!(program.mem, [0, -5, 0, 0]);
 
-    let program = program.next(&mut input);
+    let program = program.next(&mut input).unwrap();
 
     assert_eq!(program.next_instruction, 3);
     assert_eq!(program.mem, [0, -5, 3, 0]);
 
     assert!(!program.done());
 
-    let program = program.next(&mut input);
+    let program = program.next(&mut input).unwrap();
 
     assert_eq!(program.next_instruction, 4);
     assert_eq!(program.mem, [0, -2, 3, 0]);
 
     assert!(program.done());
+
+    let program = Program::new(Arc::new(vec![
+        Instruction::Inp(Var::X),
+        Instruction::Inp(Var::Y),
+        Instruction::Div(Var::X, Val::Var(Var::Y)),
+    ]));
+
+    let mut input = [5, 0].iter();
+    let program = program.next(&mut input).unwrap().next(&mut input).unwrap();
+
+    assert_eq!(program.next(&mut input), Err(AluError { index: 2, fault: AluFault::DivisionByZero }));
+    assert_eq!(program.next(&mut input).unwrap_err().to_string(), "instruction 2: division by zero");
+    assert_eq!(
+        Program::new(program.instructions.clone()).next(&mut input),
+        Err(AluError { index: 0, fault: AluFault::NoInput }),
+    );
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Breakpoint {
+    // stops before running the instruction at this index (counted from 0)
+    Instruction(usize),
+    // stops before each `inp`
+    Inp,
+}
+
+// why the debugger gave back control
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Stop {
+    // before the instruction at this index
+    Breakpoint(usize),
+    // after a single instruction
+    Step,
+    End,
+    Fault(AluError),
+}
+
+// registers after running an instruction
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Step {
+    pub index: usize,
+    instruction: Instruction,
+    pub registers: [i32; 4],
+}
+
+impl Step {
+    pub fn instruction(&self) -> String {
+        self.instruction.to_string()
+    }
+}
+
+impl std::fmt::Display for Step {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let [w, x, y, z] = self.registers;
+
+        write!(f, "{:>4}  {:<12} w={} x={} y={} z={}", self.index, self.instruction.to_string(), w, x, y, z)
+    }
+}
+
+// runs a program one instruction at a time, recording the registers after each of them
+pub struct Debugger {
+    program: Program,
+    input: Vec<i32>,
+    consumed: usize,
+    breakpoints: Vec<Breakpoint>,
+    // the position we already stopped at, `resume` doesn't stop there again
+    stopped_at: Option<usize>,
+    trace: Vec<Step>,
+}
+
+impl Debugger {
+    pub fn break_at(&mut self, breakpoint: Breakpoint) {
+        self.breakpoints.push(breakpoint);
+    }
+
+    pub fn registers(&self) -> [i32; 4] {
+        self.program.mem
+    }
+
+    // index of the next instruction to run
+    pub fn position(&self) -> usize {
+        self.program.next_instruction
+    }
+
+    pub fn next_instruction(&self) -> Option<String> {
+        self.program.instructions.get(self.program.next_instruction).map(|instruction| instruction.to_string())
+    }
+
+    pub fn trace(&self) -> &[Step] {
+        &self.trace
+    }
+
+    // every instruction run so far with the registers after it, one per line
+    pub fn dump(&self) -> String {
+        self.trace.iter().map(|step| format!("{}\n", step)).collect()
+    }
+
+    pub fn step(&mut self) -> Stop {
+        if self.program.done() {
+            return Stop::End;
+        }
+
+        let mut input = self.input[self.consumed..].iter();
+
+        match self.program.next(&mut input) {
+            Ok(program) => {
+                self.consumed = self.input.len() - input.len();
+                self.program = program;
+                self.trace.push(Step {
+                    index: self.program.next_instruction - 1,
+                    instruction: self.program.instructions[self.program.next_instruction - 1].clone(),
+                    registers: self.program.mem,
+                });
+                self.stopped_at = Some(self.position());
+
+                Stop::Step
+            },
+            Err(e) => Stop::Fault(e),
+        }
+    }
+
+    // runs until the end, a fault, or a breakpoint other than the one we are stopped at
+    pub fn resume(&mut self) -> Stop {
+        loop {
+            let position = self.position();
+
+            let hit = self.breakpoints.iter().any(|&breakpoint| match breakpoint {
+                Breakpoint::Instruction(index) => index == position,
+                Breakpoint::Inp => matches!(self.program.instructions.get(position), Some(Instruction::Inp(_))),
+            });
+
+            if hit && self.stopped_at != Some(position) {
+                self.stopped_at = Some(position);
+
+                return Stop::Breakpoint(position);
+            }
+
+            match self.step() {
+                Stop::Step => self.stopped_at = None,
+                stop => return stop,
+            }
+        }
+    }
+}
+
+impl Input {
+    pub fn debugger(&self, input: Vec<i32>) -> Debugger {
+        Debugger {
+            program: Program::new(Arc::new(self.instructions.clone())),
+            input,
+            consumed: 0,
+            breakpoints: vec![],
+            stopped_at: None,
+            trace: vec![],
+        }
+    }
+}
+
+#[test]
+fn test_debugger() {
+    let input = input_generator(Day24::EXAMPLE).unwrap();
+    let mut debugger = input.debugger(vec![9, 6]);
+
+    debugger.break_at(Breakpoint::Inp);
+    debugger.break_at(Breakpoint::Instruction(4));
+
+    assert_eq!(debugger.next_instruction(), Some("inp w".to_string()));
+    assert_eq!(debugger.resume(), Stop::Breakpoint(0));
+    assert_eq!(debugger.trace().len(), 0);
+    assert_eq!(debugger.resume(), Stop::Breakpoint(2));
+    assert_eq!(debugger.registers(), [6, 0, 0, 0]);
+    assert_eq!(debugger.next_instruction(), Some("inp x".to_string()));
+    assert_eq!(debugger.step(), Stop::Step);
+    assert_eq!(debugger.resume(), Stop::Breakpoint(4));
+    assert_eq!(debugger.resume(), Stop::End);
+    assert_eq!(debugger.step(), Stop::End);
+    assert_eq!(debugger.trace().len(), 6);
+
+    assert_eq!(debugger.dump(), "   0  inp w        w=9 x=0 y=0 z=0
+   1  add w -3     w=6 x=0 y=0 z=0
+   2  inp x        w=6 x=6 y=0 z=0
+   3  eql w x      w=1 x=6 y=0 z=0
+   4  eql w 0      w=0 x=6 y=0 z=0
+   5  add z w      w=0 x=6 y=0 z=0
+");
+
+    let mut debugger = input.debugger(vec![9]);
+
+    assert_eq!(debugger.resume(), Stop::Fault(AluError { index: 2, fault: AluFault::NoInput }));
+    assert_eq!(debugger.position(), 2);
+    assert_eq!(debugger.trace().len(), 2);
+
+    let mut debugger = input.debugger(vec![9, 6]);
+
+    debugger.break_at(Breakpoint::Instruction(0));
+
+    assert_eq!(debugger.resume(), Stop::Breakpoint(0));
+    assert_eq!(debugger.registers(), [0, 0, 0, 0]);
+    assert_eq!(debugger.resume(), Stop::End);
+
+    // a breakpoint reached with `step` is already stopped at
+    let mut debugger = input.debugger(vec![9, 6]);
+
+    debugger.break_at(Breakpoint::Instruction(1));
+
+    assert_eq!(debugger.step(), Stop::Step);
+    assert_eq!(debugger.resume(), Stop::End);
+}
+
+type Pass = fn(Vec<Instruction>) -> Vec<Instruction>;
+
+const PASSES: [Pass; 4] = [constant_propagation, identities, eql_fusion, dead_stores];
+
+// runs the passes again and again until they don't change anything anymore
+fn run_passes(mut instructions: Vec<Instruction>, passes: &[Pass]) -> Vec<Instruction> {
+    loop {
+        let optimized = passes.iter().fold(instructions.clone(), |instructions, pass| pass(instructions));
+
+        if optimized == instructions {
+            return optimized;
+        }
+
+        instructions = optimized;
+    }
+}
+
+// replaces the registers whose value is known by literals, and instructions
+// whose result is known by a `set`, or by nothing when it doesn't change anything
+fn constant_propagation(instructions: Vec<Instruction>) -> Vec<Instruction> {
+    // registers start at 0
+    let mut known = [Some(0); 4];
+    let mut optimized = vec![];
+
+    for instruction in instructions {
+        let a = instruction.target();
+
+        let instruction = match instruction.operand() {
+            Some(Val::Var(b)) => match known[b.index()] {
+                Some(value) => instruction.with_operand(Val::Literal(value)),
+                None => instruction,
+            },
+            _ => instruction,
+        };
+
+        let value = match (&instruction, known[a.index()], instruction.operand()) {
+            (Instruction::Set(_, Val::Literal(value)), _, _) => Some(*value),
+            // 0 * anything, without any risk of overflow
+            (Instruction::Mul(..), Some(0), _) => Some(0),
+            (_, Some(current), Some(Val::Literal(_))) if instruction.reads_target() => {
+                let mut mem = [0; 4];
+
+                a.set(&mut mem, current);
+
+                // when it crashes, the instruction is kept as it is
+                instruction.exec(&mut mem, &mut [].iter()).ok().map(|_| a.get(&mem))
+            },
+            _ => None,
+        };
+
+        match (value, &instruction, known[a.index()]) {
+            (Some(value), _, current) if current == Some(value) => {},
+            (Some(value), _, _) => optimized.push(Instruction::Set(a, Val::Literal(value))),
+            // adding to 0 is copying
+            (None, &Instruction::Add(_, b), Some(0)) => optimized.push(Instruction::Set(a, b)),
+            (None, _, _) => optimized.push(instruction),
+        }
+
+        known[a.index()] = value;
+    }
+
+    optimized
+}
+
+#[test]
+fn test_constant_propagation() {
+    let instructions = input_generator("inp w\nadd x 5\nmul x 2\nadd y x\nadd z w\nmul y 0\nmul w 0\ndiv x 0").unwrap().instructions;
+
+    assert_eq!(constant_propagation(instructions), [
+        Instruction::Inp(Var::W),
+        Instruction::Set(Var::X, Val::Literal(5)),
+        Instruction::Set(Var::X, Val::Literal(10)),
+        Instruction::Set(Var::Y, Val::Literal(10)),
+        Instruction::Set(Var::Z, Val::Var(Var::W)),
+        Instruction::Set(Var::Y, Val::Literal(0)),
+        Instruction::Mul(Var::W, Val::Literal(0)),
+        Instruction::Div(Var::X, Val::Literal(0)),
+    ]);
+}
+
+fn identities(instructions: Vec<Instruction>) -> Vec<Instruction> {
+    instructions.into_iter()
+        .filter_map(|instruction| match instruction {
+            Instruction::Add(_, Val::Literal(0)) |
+            Instruction::Mul(_, Val::Literal(1)) |
+            Instruction::Div(_, Val::Literal(1)) => None,
+            Instruction::Set(a, Val::Var(b)) if a == b => None,
+            Instruction::Mul(a, Val::Literal(0)) => Some(Instruction::Set(a, Val::Literal(0))),
+            Instruction::Eql(a, Val::Var(b)) if a == b => Some(Instruction::Set(a, Val::Literal(1))),
+            Instruction::Neq(a, Val::Var(b)) if a == b => Some(Instruction::Set(a, Val::Literal(0))),
+            instruction => Some(instruction),
+        })
+        .collect()
+}
+
+#[test]
+fn test_identities() {
+    let mut instructions = input_generator("add x 0\nmul y 1\ndiv z 1\nmul w 0\neql x x\ndiv z 2").unwrap().instructions;
+
+    instructions.push(Instruction::Set(Var::Y, Val::Var(Var::Y)));
+
+    assert_eq!(identities(instructions), [
+        Instruction::Set(Var::W, Val::Literal(0)),
+        Instruction::Set(Var::X, Val::Literal(1)),
+        Instruction::Div(Var::Z, Val::Literal(2)),
+    ]);
+}
+
+// `eql a b` followed by `eql a 0` is `neq a b` (and the other way around)
+fn eql_fusion(instructions: Vec<Instruction>) -> Vec<Instruction> {
+    let mut fused: Vec<Instruction> = vec![];
+
+    for instruction in instructions {
+        let previous = match (fused.last(), &instruction) {
+            (Some(&Instruction::Eql(a, b)), &Instruction::Eql(c, Val::Literal(0))) if a == c => Instruction::Neq(a, b),
+            (Some(&Instruction::Neq(a, b)), &Instruction::Eql(c, Val::Literal(0))) if a == c => Instruction::Eql(a, b),
+            _ => {
+                fused.push(instruction);
+
+                continue;
+            },
+        };
+
+        *fused.last_mut().unwrap() = previous;
+    }
+
+    fused
+}
+
+#[test]
+fn test_eql_fusion() {
+    let instructions = input_generator("eql x w\neql x 0\neql y w\neql y 0\neql y 0\neql z w\neql y 0").unwrap().instructions;
+
+    assert_eq!(eql_fusion(instructions), [
+        Instruction::Neq(Var::X, Val::Var(Var::W)),
+        Instruction::Eql(Var::Y, Val::Var(Var::W)),
+        Instruction::Eql(Var::Z, Val::Var(Var::W)),
+        Instruction::Eql(Var::Y, Val::Literal(0)),
+    ]);
+}
+
+// removes the instructions whose result is overwritten before being read,
+// all the registers are part of the result of the program
+fn dead_stores(instructions: Vec<Instruction>) -> Vec<Instruction> {
+    let mut live = [true; 4];
+    let mut kept = vec![];
+
+    for instruction in instructions.into_iter().rev() {
+        let a = instruction.target().index();
+
+        if !live[a] && !instruction.has_effects() {
+            continue;
+        }
+
+        live[a] = instruction.reads_target();
+
+        if let Some(Val::Var(b)) = instruction.operand() {
+            live[b.index()] = true;
+        }
+
+        kept.push(instruction);
+    }
+
+    kept.reverse();
+
+    kept
+}
+
+#[test]
+fn test_dead_stores() {
+    let instructions = vec![
+        Instruction::Set(Var::X, Val::Literal(5)),
+        Instruction::Set(Var::X, Val::Literal(10)),
+        Instruction::Eql(Var::Y, Val::Var(Var::X)),
+        Instruction::Inp(Var::Y),
+        Instruction::Div(Var::Y, Val::Literal(0)),
+        Instruction::Eql(Var::W, Val::Literal(1)),
+        Instruction::Set(Var::W, Val::Literal(2)),
+    ];
+
+    assert_eq!(dead_stores(instructions), [
+        Instruction::Set(Var::X, Val::Literal(10)),
+        Instruction::Inp(Var::Y),
+        Instruction::Div(Var::Y, Val::Literal(0)),
+        Instruction::Set(Var::W, Val::Literal(2)),
+    ]);
+}
+
+fn run(instructions: &[Instruction], input: &[i32]) -> Result<[i32; 4], AluFault> {
+    let mut mem = [0; 4];
+    let mut input = input.iter();
+
+    for instruction in instructions {
+        instruction.exec(&mut mem, &mut input)?;
+    }
+
+    Ok(mem)
+}
+
+// xorshift, good enough to pick inputs
+struct Random(u64);
+
+impl Random {
+    fn between(&mut self, min: i32, max: i32) -> i32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+
+        min + (self.0 % (max - min + 1) as u64) as i32
+    }
+}
+
+impl Input {
+    pub fn optimized(&self) -> Self {
+        Self { instructions: run_passes(self.instructions.clone(), &PASSES) }
+    }
+
+    // runs both programs on `runs` random inputs, half of them only made of digits,
+    // and gives the first input for which they end with different registers or faults
+    pub fn validate(&self, optimized: &Input, runs: usize, seed: u64) -> Result<(), Vec<i32>> {
+        let inputs = self.instructions.iter().filter(|instruction| matches!(instruction, Instruction::Inp(_))).count();
+        let mut random = Random(seed.max(1));
+
+        for run_index in 0..runs {
+            let (min, max) = if run_index % 2 == 0 { (1, 9) } else { (-100, 100) };
+            let input = (0..inputs).map(|_| random.between(min, max)).collect::<Vec<_>>();
+
+            if run(&self.instructions, &input) != run(&optimized.instructions, &input) {
+                return Err(input);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_optimizer() {
+    let input = input_generator(&monad_program(&MONAD_BLOCKS)).unwrap();
+    let optimized = input.optimized();
+
+    let expected = "inp w
+set x 11
+neq x w
+set y 25
+mul y x
+add y 1
+set y w
+add y 6
+mul y x
+set z y
+inp w
+set x z
+mod x 26
+add x 13
+neq x w
+set y 25
+mul y x
+add y 1
+mul z y
+set y w
+add y 14
+mul y x
+add z y";
+
+    // `mul x 0` and `add x z` become `set x z`, `eql x w` and `eql x 0` become `neq x w`…
+    // and the first block knows z is still 0
+    assert_eq!(
+        optimized.instructions[..23].iter().map(Instruction::to_string).collect::<Vec<_>>(),
+        expected.lines().collect::<Vec<_>>(),
+    );
+    assert_eq!((input.instructions.len(), optimized.instructions.len()), (252, 186));
+    assert_eq!(optimized.validate(&input, 1000, 42), Ok(()));
+    assert_eq!(input.validate(&optimized, 1000, 42), Ok(()));
+
+    let example = input_generator(Day24::EXAMPLE).unwrap();
+
+    assert_eq!(example.validate(&example.optimized(), 1000, 42), Ok(()));
+
+    // a wrong optimization gets caught
+    let mut wrong = optimized.clone();
+
+    wrong.instructions.retain(|instruction| !matches!(instruction, Instruction::Mod(..)));
+
+    assert!(input.validate(&wrong, 1000, 42).is_err());
+}
+
+// MONAD checks one digit per block of instructions, all blocks being the same
+// except for the 3 literals marked with {}
+const BLOCK: [&str; 18] = [
+    "inp w", "mul x 0", "add x z", "mod x 26", "div z {}", "add x {}", "eql x w", "eql x 0", "mul y 0",
+    "add y 25", "mul y x", "add y 1", "mul z y", "mul y 0", "add y w", "add y {}", "mul y x", "add z y",
+];
+
+const MODEL_NUMBER_DIGITS: usize = 14;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum MonadError {
+    // there must be one block per digit, each one starting with `inp w`
+    Blocks(usize),
+    // the instruction at `index` (counted from 0) isn't the one of a MONAD block
+    Shape { index: usize, expected: String, found: Option<String> },
+    // the block of this digit takes a digit out of z while there is none
+    EmptyStack { digit: usize },
+    // the block of this digit puts a digit in z that never gets taken out
+    Leftover { digit: usize },
+    Impossible(Constraint),
+    // the search went through every model number, `analysis` tells why the analyzer couldn't help
+    NoModelNumber { analysis: Box<MonadError> },
+}
+
+impl std::fmt::Display for MonadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MonadError::Blocks(blocks) => write!(
+                f, "expected {} blocks starting with `inp w`, found {}", MODEL_NUMBER_DIGITS, blocks,
+            ),
+            MonadError::Shape { index, expected, found } => write!(
+                f,
+                "instruction {}: expected `{}`, found {}",
+                index,
+                expected,
+                found.as_ref().map_or("the end of the program".to_string(), |found| format!("`{}`", found)),
+            ),
+            MonadError::EmptyStack { digit } => write!(f, "digit {} is compared to a previous digit but there is none left", digit),
+            MonadError::Leftover { digit } => write!(f, "digit {} is never compared to a later digit", digit),
+            MonadError::Impossible(constraint) => write!(f, "no digits can satisfy {}", constraint),
+            MonadError::NoModelNumber { analysis } => write!(f, "no model number leaves 0 in z ({})", analysis),
+        }
+    }
+}
+
+impl Error for MonadError {}
+
+// digit `pop` must be equal to digit `push` + `offset` (digits are counted from 0)
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Constraint {
+    pub push: usize,
+    pub pop: usize,
+    pub offset: i32,
+}
+
+impl std::fmt::Display for Constraint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "digit {} = digit {} {:+}", self.pop, self.push, self.offset)
+    }
+}
+
+// z is used as a stack of base 26 numbers: blocks with `div z 1` push their digit
+// (plus a literal) and blocks with `div z 26` pop it and compare it to their own
+// digit (plus another literal). when they differ, the digit is pushed back and
+// z can never get back to 0, so each pair of blocks gives a constraint
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Monad {
+    pub constraints: Vec<Constraint>,
+}
+
+impl Monad {
+    // `push_digit` picks the digit of the push block for an offset, the digit of
+    // the pop block follows from it
+    fn model_number(&self, push_digit: impl Fn(i32) -> i32) -> String {
+        let mut digits = [0; MODEL_NUMBER_DIGITS];
+
+        for constraint in &self.constraints {
+            digits[constraint.push] = push_digit(constraint.offset);
+            digits[constraint.pop] = digits[constraint.push] + constraint.offset;
+        }
+
+        digits.iter().map(|digit| digit.to_string()).collect()
+    }
+
+    pub fn largest(&self) -> String {
+        self.model_number(|offset| 9.min(9 - offset))
+    }
+
+    pub fn smallest(&self) -> String {
+        self.model_number(|offset| 1.max(1 - offset))
+    }
+}
+
+impl Input {
+    pub fn analyze(&self) -> Result<Monad, MonadError> {
+        let instructions = &self.instructions;
+
+        if !matches!(instructions.first(), Some(Instruction::Inp(_))) {
+            return Err(MonadError::Shape {
+                index: 0,
+                expected: BLOCK[0].to_string(),
+                found: instructions.first().map(|instruction| instruction.to_string()),
+            });
+        }
+
+        let starts = (0..instructions.len())
+            .filter(|&index| matches!(instructions[index], Instruction::Inp(_)))
+            .collect::<Vec<_>>();
+
+        if starts.len() != MODEL_NUMBER_DIGITS {
+            return Err(MonadError::Blocks(starts.len()));
+        }
+
+        let mut stack: Vec<(usize, i32)> = vec![];
+        let mut constraints = vec![];
+
+        for (digit, &start) in starts.iter().enumerate() {
+            let end = starts.get(digit + 1).cloned().unwrap_or(instructions.len());
+            let [div, check, add] = block_literals(instructions, start, end)?;
+
+            match div {
+                1 if check > 9 => stack.push((digit, add)),
+                // the digit could be equal to the top of the stack plus `check`
+                1 => return Err(MonadError::Shape {
+                    index: start + 5,
+                    expected: "add x <n>` with n > 9 after `div z 1".to_string(),
+                    found: Some(instructions[start + 5].to_string()),
+                }),
+                26 => match stack.pop() {
+                    Some((push, push_add)) => {
+                        let constraint = Constraint { push, pop: digit, offset: push_add + check };
+
+                        if constraint.offset.abs() > 8 {
+                            return Err(MonadError::Impossible(constraint));
+                        }
+
+                        constraints.push(constraint);
+                    },
+                    None => return Err(MonadError::EmptyStack { digit }),
+                },
+                _ => return Err(MonadError::Shape {
+                    index: start + 4,
+                    expected: "div z 1` or `div z 26".to_string(),
+                    found: Some(instructions[start + 4].to_string()),
+                }),
+            }
+
+            // pushed digits must stay between 1 and 25, 0 would be invisible in z
+            if div == 1 && !(0..=16).contains(&add) {
+                return Err(MonadError::Shape {
+                    index: start + 15,
+                    expected: "add y <n>` with 0 <= n <= 16".to_string(),
+                    found: Some(instructions[start + 15].to_string()),
+                });
+            }
+        }
+
+        match stack.first() {
+            Some(&(digit, _)) => Err(MonadError::Leftover { digit }),
+            None => Ok(Monad { constraints }),
+        }
+    }
+}
+
+// the 3 literals of the block going from `start` to `end` (excluded)
+fn block_literals(instructions: &[Instruction], start: usize, end: usize) -> Result<[i32; 3], MonadError> {
+    let mut literals = vec![];
+
+    for (offset, pattern) in BLOCK.iter().enumerate() {
+        let index = start + offset;
+        let shape_error = || MonadError::Shape {
+            index,
+            expected: pattern.replace("{}", "<n>"),
+            found: instructions.get(index).map(|instruction| instruction.to_string()),
+        };
+
+        if index >= end {
+            return Err(shape_error());
+        }
+
+        let instruction = instructions[index].to_string();
+
+        match pattern.strip_suffix("{}") {
+            Some(prefix) => literals.push(
+                instruction.strip_prefix(prefix).and_then(|n| n.parse().ok()).ok_or_else(shape_error)?,
+            ),
+            None if instruction == *pattern => {},
+            None => return Err(shape_error()),
+        }
+    }
+
+    if start + BLOCK.len() < end {
+        return Err(MonadError::Shape {
+            index: start + BLOCK.len(),
+            expected: BLOCK[0].to_string(),
+            found: Some(instructions[start + BLOCK.len()].to_string()),
+        });
+    }
+
+    Ok([literals[0], literals[1], literals[2]])
+}
+
+#[cfg(test)]
+fn monad_program(blocks: &[(i32, i32, i32)]) -> String {
+    blocks.iter()
+        .flat_map(|&(div, check, add)| {
+            let mut literals = vec![div, check, add].into_iter();
+
+            BLOCK.iter().map(move |pattern| match pattern.strip_suffix("{}") {
+                Some(prefix) => format!("{}{}", prefix, literals.next().unwrap()),
+                None => pattern.to_string(),
+            })
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+const MONAD_BLOCKS: [(i32, i32, i32); 14] = [
+    (1, 11, 6), (1, 13, 14), (1, 15, 14), (26, -8, 10), (1, 13, 9), (1, 15, 12), (26, -11, 8),
+    (26, -4, 13), (26, -15, 12), (1, 14, 6), (1, 14, 9), (26, -1, 15), (26, -8, 4), (26, -14, 10),
+];
+
+#[test]
+fn test_analyze() {
+    let input = input_generator(&monad_program(&MONAD_BLOCKS)).unwrap();
+    let monad = input.analyze().unwrap();
+
+    assert_eq!(monad.constraints[0], Constraint { push: 2, pop: 3, offset: 6 });
+    assert_eq!(monad.constraints.len(), 7);
+    assert_eq!(monad.largest(), "99394899891971");
+    assert_eq!(monad.smallest(), "92171126131911");
+
+    // the ALU agrees with both of them, but not when a digit changes
+    for (model_number, valid) in [("99394899891971", true), ("92171126131911", true), ("99394899891981", false)] {
+        let digits = model_number.chars().map(|c| c.to_digit(10).unwrap() as i32).collect::<Vec<_>>();
+        let mut program = Program::new(Arc::new(input.instructions.clone()));
+        let mut digits = digits.iter();
+
+        while !program.done() {
+            program = program.next(&mut digits).unwrap();
+        }
+
+        assert_eq!(Var::Z.get(&program.mem) == 0, valid);
+    }
+
+    assert_eq!(part1(&input), Ok("99394899891971".to_string()));
+    assert_eq!(part2(&input), Ok("92171126131911".to_string()));
+}
+
+#[test]
+fn test_analyze_errors() {
+    let analyze = |program: &str| input_generator(program).unwrap().analyze().unwrap_err();
+
+    assert_eq!(analyze(Day24::EXAMPLE), MonadError::Blocks(2));
+    assert_eq!(
+        analyze(&monad_program(&MONAD_BLOCKS).replacen("mod x 26", "mod x 25", 2)).to_string(),
+        "instruction 3: expected `mod x 26`, found `mod x 25`",
+    );
+    assert_eq!(
+        analyze(&format!("add z 1\n{}", monad_program(&MONAD_BLOCKS))).to_string(),
+        "instruction 0: expected `inp w`, found `add z 1`",
+    );
+    assert_eq!(
+        analyze(&monad_program(&MONAD_BLOCKS).replacen("\nadd z y", "", 1)).to_string(),
+        "instruction 17: expected `add z y`, found `inp w`",
+    );
+    assert_eq!(
+        analyze(monad_program(&MONAD_BLOCKS).trim_end_matches("\nadd z y")).to_string(),
+        "instruction 251: expected `add z y`, found the end of the program",
+    );
+
+    let mut blocks = MONAD_BLOCKS;
+
+    blocks[4] = (26, -14, 9);
+
+    assert_eq!(analyze(&monad_program(&blocks)), MonadError::EmptyStack { digit: 8 });
+
+    blocks[4] = (1, 5, 9);
+
+    assert_eq!(
+        analyze(&monad_program(&blocks)).to_string(),
+        "instruction 77: expected `add x <n>` with n > 9 after `div z 1`, found `add x 5`",
+    );
+
+    let mut blocks = MONAD_BLOCKS;
+
+    blocks[13] = (1, 14, 10);
+
+    assert_eq!(analyze(&monad_program(&blocks)), MonadError::Leftover { digit: 0 });
+
+    blocks[13] = (26, 3, 10);
+
+    assert_eq!(
+        analyze(&monad_program(&blocks)).to_string(),
+        "no digits can satisfy digit 13 = digit 0 +9",
+    );
+
+    blocks[13] = (26, -15, 10);
+
+    assert_eq!(
+        analyze(&monad_program(&blocks)).to_string(),
+        "no digits can satisfy digit 13 = digit 0 -9",
+    );
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Opcode {
+    Add,
+    Mul,
+    Div,
+    Mod,
+    Eql,
+    Set,
+    Neq,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Operand {
+    Register(usize),
+    Literal(i32),
+    // the input of the block
+    Input,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+struct Op {
+    opcode: Opcode,
+    target: usize,
+    operand: Operand,
+}
+
+// a program flattened for speed: registers are indexes, `inp a` becomes `set a <input>`
+// and the ops are grouped in blocks starting at each `inp`, so that running the
+// block of an input is one tight loop over 4 registers
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Compiled {
+    // one per instruction, errors use the same indexes as the interpreter
+    ops: Vec<Op>,
+    // index of the first op of each block
+    starts: Vec<usize>,
+}
+
+impl Opcode {
+    fn apply(self, a: i32, b: i32) -> Result<i32, AluFault> {
+        match self {
+            Opcode::Add => a.checked_add(b).ok_or(AluFault::Overflow),
+            Opcode::Mul => a.checked_mul(b).ok_or(AluFault::Overflow),
+            Opcode::Div | Opcode::Mod if b == 0 => Err(AluFault::DivisionByZero),
+            Opcode::Div => a.checked_div(b).ok_or(AluFault::Overflow),
+            Opcode::Mod if a < 0 || b < 0 => Err(AluFault::NegativeModulo),
+            Opcode::Mod => Ok(a % b),
+            Opcode::Eql => Ok((a == b) as i32),
+            Opcode::Set => Ok(b),
+            Opcode::Neq => Ok((a != b) as i32),
+        }
+    }
+}
+
+impl Compiled {
+    fn new(instructions: &[Instruction]) -> Self {
+        let ops = instructions.iter()
+            .map(|instruction| {
+                let opcode = match instruction {
+                    Instruction::Add(..) => Opcode::Add,
+                    Instruction::Mul(..) => Opcode::Mul,
+                    Instruction::Div(..) => Opcode::Div,
+                    Instruction::Mod(..) => Opcode::Mod,
+                    Instruction::Eql(..) => Opcode::Eql,
+                    Instruction::Set(..) | Instruction::Inp(_) => Opcode::Set,
+                    Instruction::Neq(..) => Opcode::Neq,
+                };
+
+                let operand = match instruction.operand() {
+                    Some(Val::Var(b)) => Operand::Register(b.index()),
+                    Some(Val::Literal(b)) => Operand::Literal(b),
+                    None => Operand::Input,
+                };
+
+                Op { opcode, target: instruction.target().index(), operand }
+            })
+            .collect::<Vec<_>>();
+
+        let starts = (0..ops.len()).filter(|&index| ops[index].operand == Operand::Input).collect();
+
+        Self { ops, starts }
+    }
+
+    // how many inputs the program reads, one per block
+    pub fn inputs(&self) -> usize {
+        self.starts.len()
+    }
+
+    fn exec(&self, ops: Range<usize>, mut mem: [i32; 4], input: i32) -> Result<[i32; 4], AluError> {
+        for index in ops {
+            let op = self.ops[index];
+
+            let b = match op.operand {
+                Operand::Register(b) => mem[b],
+                Operand::Literal(b) => b,
+                Operand::Input => input,
+            };
+
+            mem[op.target] = op.opcode.apply(mem[op.target], b).map_err(|fault| AluError { index, fault })?;
+        }
+
+        Ok(mem)
+    }
+
+    // runs the ops before the first `inp`
+    pub fn start(&self) -> Result<[i32; 4], AluError> {
+        self.exec(0..self.starts.first().cloned().unwrap_or(self.ops.len()), [0; 4], 0)
+    }
+
+    // runs the `inp` of the block with `input`, and everything up to the next `inp`
+    pub fn run_block(&self, block: usize, mem: [i32; 4], input: i32) -> Result<[i32; 4], AluError> {
+        let end = self.starts.get(block + 1).cloned().unwrap_or(self.ops.len());
+
+        self.exec(self.starts[block]..end, mem, input)
+    }
+
+    pub fn run(&self, input: &[i32]) -> Result<[i32; 4], AluError> {
+        let mut mem = self.start()?;
+
+        for block in 0..self.inputs() {
+            let input = *input.get(block).ok_or(AluError { index: self.starts[block], fault: AluFault::NoInput })?;
+
+            mem = self.run_block(block, mem, input)?;
+        }
+
+        Ok(mem)
+    }
+}
+
+impl Input {
+    pub fn compile(&self) -> Compiled {
+        Compiled::new(&self.instructions)
+    }
+}
+
+#[cfg(test)]
+fn interpret(instructions: &[Instruction], input: &[i32]) -> Result<[i32; 4], AluError> {
+    let mut program = Program::new(Arc::new(instructions.to_vec()));
+    let mut input = input.iter();
+
+    while !program.done() {
+        program = program.next(&mut input)?;
+    }
+
+    Ok(program.mem)
+}
+
+#[test]
+fn test_compiled() {
+    let program = input_generator("mul x 2\ninp w\nadd z w\ninp x\nmul z x\ndiv z y").unwrap();
+    let compiled = program.compile();
+
+    assert_eq!(compiled.inputs(), 2);
+    assert_eq!(compiled.start(), Ok([0; 4]));
+    assert_eq!(compiled.run_block(0, [0; 4], 3), Ok([3, 0, 0, 3]));
+    assert_eq!(compiled.run(&[3]), Err(AluError { index: 3, fault: AluFault::NoInput }));
+    assert_eq!(compiled.run(&[3, 4]), Err(AluError { index: 5, fault: AluFault::DivisionByZero }));
+    assert_eq!(compiled.run(&[3, 4]), interpret(&program.instructions, &[3, 4]));
+
+    // same registers and same errors as the interpreter, before and after optimization
+    let mut random = Random(42);
+
+    for program in [input_generator(&monad_program(&MONAD_BLOCKS)).unwrap(), input_generator(Day24::EXAMPLE).unwrap()] {
+        let (compiled, optimized) = (program.compile(), program.optimized().compile());
+
+        for run_index in 0..1000 {
+            let (min, max) = if run_index % 2 == 0 { (1, 9) } else { (-100, 100) };
+            let input = (0..compiled.inputs()).map(|_| random.between(min, max)).collect::<Vec<_>>();
+            let expected = interpret(&program.instructions, &input);
+
+            assert_eq!(compiled.run(&input), expected);
+            assert_eq!(optimized.run(&input).map_err(|e| e.fault), expected.map_err(|e| e.fault));
+        }
+    }
+}
+
+// how `search` runs the program
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Backend {
+    // step by step, cloning the program after each instruction
+    Interpreter,
+    Compiled,
 }
 
 type Output = String;
@@ -286,11 +1441,17 @@ fn monad(program: Program, s: String, dead_ends: &mut HashSet<Program>, options:
 
     let mut empty_input = [].iter().cloned();
 
-    let program = program.advance_unless_input(&mut empty_input);
+    // the ALU crashing means the model number is invalid
+    let program = match program.advance_unless_input(&mut empty_input) {
+        Ok(program) => program,
+        Err(_) => return None,
+    };
 
     for &i in options {
-        let p = program.next(&mut [i].iter());
-        let p = p.advance_unless_input(&mut empty_input);
+        let p = match program.next(&mut [i].iter()).and_then(|p| p.advance_unless_input(&mut empty_input)) {
+            Ok(p) => p,
+            Err(_) => continue,
+        };
         if let Some(res) = monad(p, format!("{}{}", s, i), dead_ends, options) {
             return Some(res);
         }
@@ -301,26 +1462,133 @@ fn monad(program: Program, s: String, dead_ends: &mut HashSet<Program>, options:
     None
 }
 
-#[allow(unused_variables)]
-#[aoc(day24, part1)]
-fn part1(input: &Input) -> Output {
-    let mut instructions = input.instructions.clone();
+// same as `monad`, one whole block at a time
+fn monad_compiled(
+    compiled: &Compiled,
+    block: usize,
+    mem: [i32; 4],
+    s: String,
+    dead_ends: &mut HashSet<(usize, [i32; 4])>,
+    options: &[i32],
+) -> Option<String> {
+    if block == compiled.inputs() {
+        return match mem[3] {
+            0 => Some(s),
+            _ => None,
+        };
+    }
 
-    Program::optimize(&mut instructions);
-    
-    let program = Program::new(Arc::new(instructions));
+    if dead_ends.contains(&(block, mem)) {
+        return None;
+    }
+
+    for &i in options {
+        // the ALU crashing means the model number is invalid
+        if let Ok(next) = compiled.run_block(block, mem, i) {
+            if let Some(res) = monad_compiled(compiled, block + 1, next, format!("{}{}", s, i), dead_ends, options) {
+                return Some(res);
+            }
+        }
+    }
 
-    monad(program, "".to_string(), &mut HashSet::new(), &[9, 8, 7, 6, 5, 4, 3, 2, 1]).unwrap()
+    dead_ends.insert((block, mem));
+
+    None
+}
+
+// tries every digit in the order of `options` until the program ends with z = 0
+fn search(input: &Input, options: &[i32], backend: Backend) -> Option<Output> {
+    let mut instructions = input.optimized().instructions;
+
+    match backend {
+        Backend::Interpreter => {
+            Program::optimize(&mut instructions);
+
+            monad(Program::new(Arc::new(instructions)), "".to_string(), &mut HashSet::new(), options)
+        },
+        Backend::Compiled => {
+            let compiled = Compiled::new(&instructions);
+            let mem = compiled.start().ok()?;
+
+            monad_compiled(&compiled, 0, mem, "".to_string(), &mut HashSet::new(), options)
+        },
+    }
+}
+
+#[test]
+fn test_search() {
+    // digit 2 = digit 1 + 6, digit 3 = digit 0 - 8
+    let input = input_generator(&monad_program(&[(1, 11, 6), (1, 13, 14), (26, -8, 10), (26, -14, 10)])).unwrap();
+    let example = input_generator(Day24::EXAMPLE).unwrap();
+
+    for backend in [Backend::Interpreter, Backend::Compiled] {
+        assert_eq!(search(&input, &[9, 8, 7, 6, 5, 4, 3, 2, 1], backend), Some("9391".to_string()));
+        assert_eq!(search(&input, &[1, 2, 3, 4, 5, 6, 7, 8, 9], backend), Some("9171".to_string()));
+        assert_eq!(search(&example, &[9, 8, 7, 6, 5, 4, 3, 2, 1], backend), Some("96".to_string()));
+        assert_eq!(search(&example, &[1, 2, 3, 4, 5, 6, 7, 8, 9], backend), Some("41".to_string()));
+        assert_eq!(search(&input, &[2, 3], backend), None);
+    }
+}
+
+// programs that don't look like MONAD still get searched digit by digit
+fn model_number(input: &Input, pick: fn(&Monad) -> String, options: &[i32]) -> Result<Output, MonadError> {
+    match input.analyze() {
+        Ok(monad) => Ok(pick(&monad)),
+        Err(analysis) => search(input, options, Backend::Compiled)
+            .ok_or_else(|| MonadError::NoModelNumber { analysis: Box::new(analysis) }),
+    }
+}
+
+#[allow(unused_variables)]
+#[aoc(day24, part1)]
+fn part1(input: &Input) -> Result<Output, MonadError> {
+    model_number(input, Monad::largest, &[9, 8, 7, 6, 5, 4, 3, 2, 1])
 }
 
 #[allow(unused_variables)]
 #[aoc(day24, part2)]
-fn part2(input: &Input) -> Output {
-    let mut instructions = input.instructions.clone();
+fn part2(input: &Input) -> Result<Output, MonadError> {
+    model_number(input, Monad::smallest, &[1, 2, 3, 4, 5, 6, 7, 8, 9])
+}
 
-    Program::optimize(&mut instructions);
-    
-    let program = Program::new(Arc::new(instructions));
+#[test]
+fn no_model_number() {
+    let input = input_generator("inp w\nadd z 1").unwrap();
+    let error = MonadError::NoModelNumber { analysis: Box::new(MonadError::Blocks(1)) };
+
+    assert_eq!(part1(&input), Err(error.clone()));
+    assert_eq!(part2(&input), Err(error));
+    assert_eq!(
+        crate::solve(24, 1, "inp w\nadd z 1").unwrap_err().to_string(),
+        "day 24 part 1: no model number leaves 0 in z (expected 14 blocks starting with `inp w`, found 1)",
+    );
+}
+
+pub struct Day24;
+
+impl Puzzle for Day24 {
+    const DAY: u8 = 24;
+    const EXAMPLE: &'static str = "inp w
+add w -3
+inp x
+eql w x
+eql w 0
+add z w";
+    const EXAMPLE_ANSWERS: [Option<Answer>; 2] = [Some(Answer::Text(Cow::Borrowed("96"))), Some(Answer::Text(Cow::Borrowed("41")))];
+
+    type Input = Input;
+    type Output1 = Result<Output, MonadError>;
+    type Output2 = Result<Output, MonadError>;
 
-    monad(program, "".to_string(), &mut HashSet::new(), &[1, 2, 3, 4, 5, 6, 7, 8, 9]).unwrap()
-}
\ No newline at end of file
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
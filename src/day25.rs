@@ -1,10 +1,12 @@
-use std::{str::FromStr, convert::Infallible, fmt::Debug};
+use std::{str::FromStr, fmt::Debug};
 
 use parse_display::{Display, FromStr};
 
+use crate::{grid::Grid, parse::ParseError, puzzle::Puzzle, solve::Answer};
+
 #[derive(PartialEq, Clone)]
-struct Input {
-    seafloor: Vec<Vec<Option<SeaCucumber>>>,
+pub struct Input {
+    seafloor: Grid<Option<SeaCucumber>>,
 }
 
 #[derive(PartialEq, Display, FromStr, Clone, Copy)]
@@ -17,19 +19,20 @@ enum SeaCucumber {
 
 #[allow(unused_variables)]
 #[aoc_generator(day25)]
-fn input_generator(input: &str) -> Input {
-    input.parse::<Input>().unwrap()
+fn input_generator(input: &str) -> Result<Input, ParseError> {
+    input.parse::<Input>()
 }
 
 impl FromStr for Input {
-    type Err = Infallible;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Input {
-            seafloor: s.lines()
-                .map(|line| line.chars().map(|c| c.to_string().parse::<SeaCucumber>().ok()).collect::<Vec<_>>())
-                .collect::<Vec<_>>(),
-        })
+        let seafloor = Grid::parse(25, s, "a sea cucumber (> or v) or an empty location (.)", |c| match c {
+            '.' => Some(None),
+            _ => c.to_string().parse::<SeaCucumber>().ok().map(Some),
+        })?;
+
+        Ok(Input { seafloor })
     }
 }
 
@@ -37,14 +40,20 @@ impl FromStr for Input {
 #[test]
 fn part1_provided_exemple_input() {
     assert_eq!(input_generator(".>
-vv"), Input {
-    seafloor: vec![
-        vec![None, Some(SeaCucumber::East)],
-        vec![Some(SeaCucumber::South), Some(SeaCucumber::South)],
-    ]
+vv").unwrap(), Input {
+    seafloor: Grid::new(2, vec![
+        None, Some(SeaCucumber::East),
+        Some(SeaCucumber::South), Some(SeaCucumber::South),
+    ]),
 });
 }
 
+#[test]
+fn input_errors() {
+    assert_eq!(input_generator(".>\nv<").err(), Some(ParseError::new(25, 2, 2, "a sea cucumber (> or v) or an empty location (.)")));
+    assert_eq!(input_generator(".>\nv").err(), Some(ParseError::new(25, 2, 2, "2 cells per line")));
+}
+
 impl Debug for SeaCucumber {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -59,7 +68,7 @@ impl Debug for Input {
         write!(
             f,
             "{}", 
-            self.seafloor.iter()
+            self.seafloor.rows()
                 .map(|line| line.iter()
                     .map(|sea_cucumber| sea_cucumber.map_or(".".to_string(), |sea_cucumber| format!("{:?}", sea_cucumber)))
                     .collect::<Vec<_>>()
@@ -76,7 +85,7 @@ impl Debug for Input {
 #[test]
 fn test_debug() {
     assert_eq!(format!("{:?}", input_generator(".>
-vv")), ".>
+vv").unwrap()), ".>
 vv");
 }
 
@@ -84,46 +93,25 @@ type Output = usize;
 
 impl Input {
     pub fn step(&mut self) -> bool {
-        let height = self.seafloor.len();
-        let width = self.seafloor[0].len();
-
         let mut changes = false;
 
         for direction in [SeaCucumber::East, SeaCucumber::South] {
-            let (row_count, col_count) = match direction {
-                SeaCucumber::East => (height, width),
-                SeaCucumber::South => (width, height),
+            let delta = match direction {
+                SeaCucumber::East => (0, 1),
+                SeaCucumber::South => (1, 0),
             };
 
-            for row in 0..row_count {
-                let mut moves = vec![];
-
-                for col in 0..col_count {
-                    let from = match direction {
-                        SeaCucumber::East => (row, col),
-                        SeaCucumber::South => (col, row),
-                    };
-                    if let Some(d) = self.seafloor[from.0][from.1] {
-                        if d == direction {
-                            let target = (col + 1) % col_count;
-                            let to = match direction {
-                                SeaCucumber::East => (row, target),
-                                SeaCucumber::South => (target, row),
-                            };
-                                
-                            if self.seafloor[to.0][to.1].is_none() {
-                                moves.push((from, to));
-                            }
-                        }
-                    }
-                }
-                
-                for (from, to) in moves {
-                    self.seafloor[from.0][from.1] = None;
-                    self.seafloor[to.0][to.1] = Some(direction);
-
-                    changes = true;
-                }
+            let moves = self.seafloor.iter()
+                .filter(|&(_, &sea_cucumber)| sea_cucumber == Some(direction))
+                .map(|(from, _)| (from, self.seafloor.wrapping_offset(from, delta)))
+                .filter(|&(_, to)| self.seafloor[to].is_none())
+                .collect::<Vec<_>>();
+
+            for (from, to) in moves {
+                self.seafloor[from] = None;
+                self.seafloor[to] = Some(direction);
+
+                changes = true;
             }
         }
 
@@ -133,12 +121,12 @@ impl Input {
 
 #[test]
 fn test_step() {
-    let mut input = input_generator(">>");
+    let mut input = input_generator(">>").unwrap();
 
     assert!(!input.step());
     assert_eq!(format!("{:?}", input), ">>");
 
-    let mut input = input_generator(">>.");
+    let mut input = input_generator(">>.").unwrap();
 
     assert!(input.step());
     assert_eq!(format!("{:?}", input), ">.>");
@@ -152,7 +140,7 @@ fn test_step() {
 
     let mut input = input_generator("v
 v
-.");
+.").unwrap();
 
     assert!(input.step());
     assert_eq!(format!("{:?}", input), "v
@@ -191,7 +179,7 @@ fn part1(input: &Input) -> Output {
 #[allow(unreachable_code)]
 #[test]
 fn part1_provided_exemple() {
-    assert_eq!(part1(&input_generator(exemple_raw_input())), 58);
+    assert_eq!(part1(&input_generator(Day25::EXAMPLE).unwrap()), 58);
 }
 
 #[allow(unused_variables)]
@@ -204,13 +192,14 @@ fn part2(input: &Input) -> Output {
 #[allow(unreachable_code)]
 #[test]
 fn part2_provided_exemple() {
-    assert_eq!(part2(&input_generator(exemple_raw_input())), todo!());
+    assert_eq!(part2(&input_generator(Day25::EXAMPLE).unwrap()), todo!());
 }
 
-#[allow(unreachable_code)]
-#[allow(dead_code)]
-fn exemple_raw_input() -> &'static str {
-    "v...>>.vv>
+pub struct Day25;
+
+impl Puzzle for Day25 {
+    const DAY: u8 = 25;
+    const EXAMPLE: &'static str = "v...>>.vv>
 .vv>>.vv..
 >>.>v>...v
 >>v>>.>.v.
@@ -218,5 +207,27 @@ v>v.vv.v..
 >.>>..v...
 .vv..>.>v.
 v.v..>>v.v
-....v..v.>"
+....v..v.>";
+    const EXAMPLE_ANSWERS: [Option<Answer>; 2] = [Some(Answer::Number(58)), None];
+
+    type Input = Input;
+    type Output1 = Output;
+    type Output2 = Output;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+
+    // there is no second part on the last day
+    fn solved(part: u8) -> bool {
+        part == 1
+    }
 }
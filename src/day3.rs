@@ -1,15 +1,24 @@
+use crate::{parse::{self, ParseError}, puzzle::Puzzle, solve::Answer};
+
 pub struct DiagnosticReport {
     pub data: Vec<Vec<bool>>
 }
 
 impl DiagnosticReport {
-    pub fn from(input: &str) -> Self {
-        let data = input
-            .lines()
-            .map(|l| l.chars().map(|c| c == '1').collect::<Vec<_>>())
-            .collect::<Vec<_>>();
-
-        Self { data }
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        let data = parse::lines(input)
+            .map(|(n, line)| line.chars()
+                .enumerate()
+                .map(|(column, c)| match c {
+                    '0' => Ok(false),
+                    '1' => Ok(true),
+                    _ => Err(ParseError::new(3, n, column + 1, "a binary digit")),
+                })
+                .collect::<Result<Vec<_>, _>>()
+            )
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self { data })
     }
 
     pub fn part1(&self) -> (u32, u32) {
@@ -58,8 +67,8 @@ impl DiagnosticReport {
 }
 
 #[aoc_generator(day3)]
-pub fn input_generator(input: &str) -> DiagnosticReport {
-    DiagnosticReport::from(input)
+pub fn input_generator(input: &str) -> Result<DiagnosticReport, ParseError> {
+    DiagnosticReport::parse(input)
 }
 
 #[aoc(day3, part1)]
@@ -74,11 +83,11 @@ pub fn part2(report: &DiagnosticReport) -> u32 {
     report.oxygen_generator_rating() * report.carbon_dioxyde_scrubber_rating()
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+pub struct Day3;
 
-    const EXEMPLE_RAW_INPUT: &str = "00100
+impl Puzzle for Day3 {
+    const DAY: u8 = 3;
+    const EXAMPLE: &'static str = "00100
 11110
 10110
 10111
@@ -90,6 +99,29 @@ mod tests {
 11001
 00010
 01010";
+    const EXAMPLE_ANSWERS: [Option<Answer>; 2] = [Some(Answer::Number(198)), Some(Answer::Number(230))];
+
+    type Input = DiagnosticReport;
+    type Output1 = u32;
+    type Output2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     const EXEMPLE_PARSED_INPUT: &[[bool;5]] = &[
         [false, false, true, false, false],
         [true, true, true, true, false],
@@ -107,16 +139,24 @@ mod tests {
 
     #[test]
     fn part2_provided_exemple() {
-        assert_eq!(part2(&input_generator(EXEMPLE_RAW_INPUT)), 230);
+        assert_eq!(part2(&input_generator(Day3::EXAMPLE).unwrap()), 230);
     }
 
     #[test]
     fn part1_provided_exemple() {
-        assert_eq!(part1(&input_generator(EXEMPLE_RAW_INPUT)), 198);
+        assert_eq!(part1(&input_generator(Day3::EXAMPLE).unwrap()), 198);
     }
 
     #[test]
     fn part1_provided_exemple_input() {
-        assert_eq!(input_generator(EXEMPLE_RAW_INPUT).data, EXEMPLE_PARSED_INPUT);
+        assert_eq!(input_generator(Day3::EXAMPLE).unwrap().data, EXEMPLE_PARSED_INPUT);
+    }
+
+    #[test]
+    fn input_errors() {
+        assert_eq!(
+            input_generator("00100\n11120").err(),
+            Some(ParseError::new(3, 2, 4, "a binary digit")),
+        );
     }
 }
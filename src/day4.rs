@@ -1,5 +1,7 @@
 use std::collections::BTreeMap;
 
+use crate::{parse::{self, ParseError}, puzzle::Puzzle, solve::Answer};
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct Board {
     pub numbers: BTreeMap<usize, (usize, usize)>,
@@ -64,22 +66,36 @@ impl Input {
 }
 
 #[aoc_generator(day4)]
-pub fn input_generator(input: &str) -> Input {
-    let mut lines = input.lines();
+pub fn input_generator(input: &str) -> Result<Input, ParseError> {
+    let mut lines = parse::lines(input);
+
+    let line = lines.next().ok_or_else(|| ParseError::new(4, 1, 1, "the drawn numbers"))?;
 
-    let numbers = lines.next().unwrap()
+    let numbers = line.1
         .split(',')
-        .map(|n| n.parse().unwrap())
-        .collect();
+        .map(|n| parse::parse(4, line, n, "a drawn number"))
+        .collect::<Result<Vec<_>, _>>()?;
 
     let mut boards = vec![];
 
-    while lines.next().is_some() {
+    while let Some((n, separator)) = lines.next() {
+        if !separator.trim().is_empty() {
+            return Err(ParseError::new(4, n, 1, "an empty line between boards"));
+        }
+
         let mut board_numbers: BTreeMap<usize, (usize, usize)> = BTreeMap::new();
 
         for row in 0..5 {
-            for (column, s) in lines.next().unwrap().split_whitespace().enumerate() {
-                board_numbers.insert(s.parse().unwrap(), (row, column));
+            let line = lines.next().ok_or_else(|| ParseError::new(4, n + row + 1, 1, "a board row"))?;
+            let mut columns = 0;
+
+            for (column, s) in line.1.split_whitespace().enumerate() {
+                board_numbers.insert(parse::parse(4, line, s, "a board number")?, (row, column));
+                columns += 1;
+            }
+
+            if columns != 5 {
+                return Err(ParseError::new(4, line.0, line.1.len() + 1, "5 numbers per board row"));
             }
         }
 
@@ -90,10 +106,10 @@ pub fn input_generator(input: &str) -> Input {
         });
     }
 
-    Input {
+    Ok(Input {
         numbers,
         boards,
-    }
+    })
 }
 
 #[aoc(day4, part1)]
@@ -108,11 +124,11 @@ pub fn part2(input: &Input) -> usize {
     input.clone().part2()
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+pub struct Day4;
 
-    const EXEMPLE_RAW_INPUT: &str = "7,4,9,5,11,17,23,2,0,14,21,24,10,16,13,6,15,25,12,22,18,20,8,19,3,26,1
+impl Puzzle for Day4 {
+    const DAY: u8 = 4;
+    const EXAMPLE: &'static str = "7,4,9,5,11,17,23,2,0,14,21,24,10,16,13,6,15,25,12,22,18,20,8,19,3,26,1
 
 22 13 17 11  0
  8  2 23  4 24
@@ -131,20 +147,42 @@ mod tests {
 18  8 23 26 20
 22 11 13  6  5
  2  0 12  3  7";
+    const EXAMPLE_ANSWERS: [Option<Answer>; 2] = [Some(Answer::Number(4512)), Some(Answer::Number(1924))];
+
+    type Input = Input;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
     #[test]
     fn part2_provided_exemple() {
-        assert_eq!(part2(&input_generator(EXEMPLE_RAW_INPUT)), 1924);
+        assert_eq!(part2(&input_generator(Day4::EXAMPLE).unwrap()), 1924);
     }
 
     #[test]
     fn part1_provided_exemple() {
-        assert_eq!(part1(&input_generator(EXEMPLE_RAW_INPUT)), 4512);
+        assert_eq!(part1(&input_generator(Day4::EXAMPLE).unwrap()), 4512);
     }
 
     #[test]
     fn part1_provided_exemple_input() {
-        assert_eq!(input_generator(EXEMPLE_RAW_INPUT), Input {
+        assert_eq!(input_generator(Day4::EXAMPLE).unwrap(), Input {
             numbers: vec![7,4,9,5,11,17,23,2,0,14,21,24,10,16,13,6,15,25,12,22,18,20,8,19,3,26,1],
             boards: vec![Board {
                 numbers: BTreeMap::from([
@@ -239,4 +277,14 @@ mod tests {
             }],
         });
     }
+
+    #[test]
+    fn input_errors() {
+        assert_eq!(input_generator("7,4,x"), Err(ParseError::new(4, 1, 5, "a drawn number")));
+        assert_eq!(input_generator("7,4\n\n1 2 3 4 5"), Err(ParseError::new(4, 4, 1, "a board row")));
+        assert_eq!(
+            input_generator("7,4\n\n1 2 3 4 5\n1 2 3 4\n"),
+            Err(ParseError::new(4, 4, 8, "5 numbers per board row")),
+        );
+    }
 }
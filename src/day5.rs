@@ -2,16 +2,18 @@ use std::cmp::Ordering;
 use std::collections::HashMap;
 use parse_display::{Display, FromStr};
 
+use crate::{parse::{self, ParseError}, puzzle::Puzzle, solve::Answer};
+
 #[derive(Display, FromStr, PartialEq, Eq, Hash, Debug)]
 #[display("{x},{y}")]
-struct Coord {
+pub struct Coord {
     x: usize,
     y: usize,
 }
 
 #[derive(Display, FromStr, PartialEq, Debug)]
 #[display("{start} -> {end}")]
-struct Line {
+pub struct Line {
     start: Coord,
     end: Coord,
 }
@@ -58,10 +60,10 @@ impl IntoIterator for &Line {
 }
 
 #[aoc_generator(day5)]
-fn input_generator(input: &str) -> Vec<Line> {
-    input.lines()
-        .map(|line| line.parse::<Line>().unwrap())
-        .collect::<Vec<_>>()
+fn input_generator(input: &str) -> Result<Vec<Line>, ParseError> {
+    parse::lines(input)
+        .map(|line| parse::parse(5, line, line.1, "a line of vents (x1,y1 -> x2,y2)"))
+        .collect()
 }
 
 #[aoc(day5, part1)]
@@ -103,11 +105,11 @@ fn part2(lines: &[Line]) -> usize {
         .count()
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+pub struct Day5;
 
-    const EXEMPLE_RAW_INPUT: &str = "0,9 -> 5,9
+impl Puzzle for Day5 {
+    const DAY: u8 = 5;
+    const EXAMPLE: &'static str = "0,9 -> 5,9
 8,0 -> 0,8
 9,4 -> 3,4
 2,2 -> 2,1
@@ -117,20 +119,42 @@ mod tests {
 3,4 -> 1,4
 0,0 -> 8,8
 5,5 -> 8,2";
+    const EXAMPLE_ANSWERS: [Option<Answer>; 2] = [Some(Answer::Number(5)), Some(Answer::Number(12))];
+
+    type Input = Vec<Line>;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
     #[test]
     fn part2_provided_exemple() {
-        assert_eq!(part2(&input_generator(EXEMPLE_RAW_INPUT)), 12);
+        assert_eq!(part2(&input_generator(Day5::EXAMPLE).unwrap()), 12);
     }
 
     #[test]
     fn part1_provided_exemple() {
-        assert_eq!(part1(&input_generator(EXEMPLE_RAW_INPUT)), 5);
+        assert_eq!(part1(&input_generator(Day5::EXAMPLE).unwrap()), 5);
     }
 
     #[test]
     fn part1_provided_exemple_input() {
-        assert_eq!(input_generator(EXEMPLE_RAW_INPUT), vec![
+        assert_eq!(input_generator(Day5::EXAMPLE).unwrap(), vec![
             Line { start: Coord { x: 0, y: 9 }, end: Coord { x: 5, y: 9 }},
             Line { start: Coord { x: 8, y: 0 }, end: Coord { x: 0, y: 8 }},
             Line { start: Coord { x: 9, y: 4 }, end: Coord { x: 3, y: 4 }},
@@ -143,4 +167,12 @@ mod tests {
             Line { start: Coord { x: 5, y: 5 }, end: Coord { x: 8, y: 2 }},
         ]);
     }
+
+    #[test]
+    fn input_errors() {
+        assert_eq!(
+            input_generator("0,9 -> 5,9\n8,0 => 0,8"),
+            Err(ParseError::new(5, 2, 1, "a line of vents (x1,y1 -> x2,y2)")),
+        );
+    }
 }
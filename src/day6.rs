@@ -1,7 +1,9 @@
 use std::collections::HashMap;
 
+use crate::{parse::{self, ParseError}, puzzle::Puzzle, solve::Answer};
+
 #[derive(Debug, Clone, PartialEq)]
-struct LanternFishes {
+pub struct LanternFishes {
     fishes: Vec<isize>,
 }
 
@@ -40,12 +42,14 @@ impl LanternFishes {
 }
 
 #[aoc_generator(day6)]
-fn input_generator(input: &str) -> LanternFishes {
-    LanternFishes {
-        fishes: input.split(',')
-            .map(|n| n.parse().unwrap())
-            .collect::<Vec<_>>(),
-    }
+fn input_generator(input: &str) -> Result<LanternFishes, ParseError> {
+    let line = (1, input.trim_end());
+
+    Ok(LanternFishes {
+        fishes: line.1.split(',')
+            .map(|n| parse::parse(6, line, n, "a lanternfish timer"))
+            .collect::<Result<Vec<_>, _>>()?,
+    })
 }
 
 #[aoc(day6, part1)]
@@ -60,20 +64,42 @@ fn part2(fishes: &LanternFishes) -> usize {
     fishes.count(256)
 }
 
+pub struct Day6;
+
+impl Puzzle for Day6 {
+    const DAY: u8 = 6;
+    const EXAMPLE: &'static str = "3,4,3,1,2";
+    const EXAMPLE_ANSWERS: [Option<Answer>; 2] = [Some(Answer::Number(5934)), Some(Answer::Number(26984457539))];
+
+    type Input = LanternFishes;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const EXEMPLE_RAW_INPUT: &str = "3,4,3,1,2";
-
     #[test]
     fn part2_provided_exemple() {
-        assert_eq!(part2(&input_generator(EXEMPLE_RAW_INPUT)), 26984457539);
+        assert_eq!(part2(&input_generator(Day6::EXAMPLE).unwrap()), 26984457539);
     }
 
     #[test]
     fn part1_provided_exemple() {
-        assert_eq!(part1(&input_generator(EXEMPLE_RAW_INPUT)), 5934);
+        assert_eq!(part1(&input_generator(Day6::EXAMPLE).unwrap()), 5934);
     }
 
     #[test]
@@ -92,6 +118,12 @@ mod tests {
 
     #[test]
     fn part1_provided_exemple_input() {
-        assert_eq!(input_generator(EXEMPLE_RAW_INPUT), LanternFishes { fishes: vec![3,4,3,1,2] });
+        assert_eq!(input_generator(Day6::EXAMPLE).unwrap(), LanternFishes { fishes: vec![3,4,3,1,2] });
+    }
+
+    #[test]
+    fn input_errors() {
+        assert_eq!(input_generator("3,4,3,1,2\n"), Ok(LanternFishes { fishes: vec![3,4,3,1,2] }));
+        assert_eq!(input_generator("3,4,,1,2"), Err(ParseError::new(6, 1, 5, "a lanternfish timer")));
     }
 }
@@ -1,16 +1,20 @@
+use crate::{parse::{self, ParseError}, puzzle::Puzzle, solve::Answer};
+
 #[derive(Debug, PartialEq)]
-struct Input {
+pub struct Input {
     crabs: Vec<isize>
 }
 
 #[aoc_generator(day7)]
-fn input_generator(input: &str) -> Input {
-    Input {
-        crabs: input
+fn input_generator(input: &str) -> Result<Input, ParseError> {
+    let line = (1, input.trim_end());
+
+    Ok(Input {
+        crabs: line.1
             .split(',')
-            .map(|n| n.parse::<isize>().unwrap())
-            .collect::<Vec<_>>(),
-    }
+            .map(|n| parse::parse(7, line, n, "a crab position"))
+            .collect::<Result<Vec<_>, _>>()?,
+    })
 }
 
 type Output = isize;
@@ -47,29 +51,54 @@ fn part2(input: &Input) -> Output {
         .min().unwrap()
 }
 
+pub struct Day7;
+
+impl Puzzle for Day7 {
+    const DAY: u8 = 7;
+    const EXAMPLE: &'static str = "16,1,2,0,4,2,7,1,2,14";
+    const EXAMPLE_ANSWERS: [Option<Answer>; 2] = [Some(Answer::Number(37)), Some(Answer::Number(168))];
+
+    type Input = Input;
+    type Output1 = Output;
+    type Output2 = Output;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
 #[allow(unreachable_code)]
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    fn exemple_raw_input() -> &'static str {
-        "16,1,2,0,4,2,7,1,2,14"
-    }
-
     #[test]
     fn part2_provided_exemple() {
-        assert_eq!(part2(&input_generator(exemple_raw_input())), 168);
+        assert_eq!(part2(&input_generator(Day7::EXAMPLE).unwrap()), 168);
     }
 
     #[test]
     fn part1_provided_exemple() {
-        assert_eq!(part1(&input_generator(exemple_raw_input())), 37);
+        assert_eq!(part1(&input_generator(Day7::EXAMPLE).unwrap()), 37);
     }
 
     #[test]
     fn part1_provided_exemple_input() {
-        assert_eq!(input_generator(exemple_raw_input()), Input {
+        assert_eq!(input_generator(Day7::EXAMPLE).unwrap(), Input {
             crabs: vec![16, 1, 2, 0, 4, 2, 7, 1, 2, 14],
         });
     }
+
+    #[test]
+    fn input_errors() {
+        assert_eq!(input_generator("16,1,-"), Err(ParseError::new(7, 1, 6, "a crab position")));
+    }
 }
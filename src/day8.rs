@@ -1,5 +1,7 @@
+use crate::{parse::{self, ParseError}, puzzle::Puzzle, solve::Answer};
+
 #[derive(Debug, PartialEq)]
-struct Input {
+pub struct Input {
     lines: Vec<Line>
 }
 
@@ -10,27 +12,36 @@ struct Line {
     output_values: Vec<u8>,
 }
 
+fn parse_digits(line: (usize, &str), digits: &str) -> Result<Vec<u8>, ParseError> {
+    digits
+        .split(' ')
+        .map(|s| s.bytes()
+            .enumerate()
+            .map(|(i, b)| match b {
+                b'a'..=b'g' => Ok(1 << (b - b'a')),
+                _ => Err(ParseError::new(8, line.0, parse::column(line.1, s) + i, "a segment (a-g)")),
+            })
+            .sum()
+        )
+        .collect()
+}
+
 #[allow(unused_variables)]
 #[aoc_generator(day8)]
-fn input_generator(input: &str) -> Input {
-    Input {
-        lines: input.lines()
+fn input_generator(input: &str) -> Result<Input, ParseError> {
+    Ok(Input {
+        lines: parse::lines(input)
             .map(|line| {
-                let (signal_patterns, output_values) = line.split_once(" | ").unwrap();
+                let (signal_patterns, output_values) = line.1.split_once(" | ")
+                    .ok_or_else(|| ParseError::new(8, line.0, line.1.len() + 1, "\" | \""))?;
 
-                Line {
-                    signal_patterns: signal_patterns
-                        .split(' ')
-                        .map(|s| s.bytes().map(|b| 1 << (b - b'a')).sum())
-                        .collect(),
-                    output_values: output_values
-                        .split(' ')
-                        .map(|s| s.bytes().map(|b| 1 << (b - b'a')).sum())
-                        .collect(),
-                }
+                Ok(Line {
+                    signal_patterns: parse_digits(line, signal_patterns)?,
+                    output_values: parse_digits(line, output_values)?,
+                })
             })
-            .collect(),
-    }
+            .collect::<Result<Vec<_>, _>>()?,
+    })
 }
 
 type Output = usize;
@@ -90,13 +101,11 @@ fn part2(input: &Input) -> Output {
         .sum()
 }
 
-#[allow(unreachable_code)]
-#[cfg(test)]
-mod tests {
-    use super::*;
+pub struct Day8;
 
-    fn exemple_raw_input() -> &'static str {
-        "be cfbegad cbdgef fgaecd cgeb fdcge agebfd fecdb fabcd edb | fdgacbe cefdb cefbgd gcbe
+impl Puzzle for Day8 {
+    const DAY: u8 = 8;
+    const EXAMPLE: &'static str = "be cfbegad cbdgef fgaecd cgeb fdcge agebfd fecdb fabcd edb | fdgacbe cefdb cefbgd gcbe
 edbfga begcd cbg gc gcadebf fbgde acbgfd abcde gfcbed gfec | fcgedb cgb dgebacf gc
 fgaebd cg bdaec gdafb agbcfd gdcbef bgcad gfac gcb cdgabef | cg cg fdcagb cbg
 fbegcd cbd adcefb dageb afcb bc aefdc ecdab fgdeca fcdbega | efabcd cedba gadfec cb
@@ -105,22 +114,44 @@ fgeab ca afcebg bdacfeg cfaedg gcfdb baec bfadeg bafgc acf | gebdcfa ecba ca fad
 dbcfg fgd bdegcaf fgec aegbdf ecdfab fbedc dacgb gdcebf gf | cefg dcbef fcge gbcadfe
 bdfegc cbegaf gecbf dfcage bdacg ed bedf ced adcbefg gebcd | ed bcgafe cdgba cbgef
 egadfb cdbfeg cegd fecab cgb gbdefca cg fgcdab egfdb bfceg | gbdfcae bgc cg cgb
-gcafb gcf dcaebfg ecagb gf abcdeg gaef cafbge fdbac fegbdc | fgae cfgab fg bagce"
+gcafb gcf dcaebfg ecagb gf abcdeg gaef cafbge fdbac fegbdc | fgae cfgab fg bagce";
+    const EXAMPLE_ANSWERS: [Option<Answer>; 2] = [Some(Answer::Number(26)), Some(Answer::Number(61229))];
+
+    type Input = Input;
+    type Output1 = Output;
+    type Output2 = Output;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
     }
 
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
+#[allow(unreachable_code)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     #[test]
     fn part2_provided_exemple() {
-        assert_eq!(part2(&input_generator(exemple_raw_input())), 61229);
+        assert_eq!(part2(&input_generator(Day8::EXAMPLE).unwrap()), 61229);
     }
 
     #[test]
     fn part1_provided_exemple() {
-        assert_eq!(part1(&input_generator(exemple_raw_input())), 26);
+        assert_eq!(part1(&input_generator(Day8::EXAMPLE).unwrap()), 26);
     }
 
     #[test]
     fn part1_provided_exemple_input() {
-        assert_eq!(input_generator(exemple_raw_input()), Input {
+        assert_eq!(input_generator(Day8::EXAMPLE).unwrap(), Input {
             lines: vec![
                 Line {
                     signal_patterns: vec![
@@ -325,4 +356,10 @@ gcafb gcf dcaebfg ecagb gf abcdeg gaef cafbge fdbac fegbdc | fgae cfgab fg bagce
             ]
         });
     }
+
+    #[test]
+    fn input_errors() {
+        assert_eq!(input_generator("be cfbegad"), Err(ParseError::new(8, 1, 11, "\" | \"")));
+        assert_eq!(input_generator("be cfbegad | fdgacbe cefxb"), Err(ParseError::new(8, 1, 25, "a segment (a-g)")));
+    }
 }
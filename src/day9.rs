@@ -1,83 +1,45 @@
 use std::collections::HashSet;
 
+use crate::{grid::{Grid, Pos}, parse::ParseError, puzzle::Puzzle, solve::Answer};
+
 #[derive(Debug, PartialEq)]
-struct Input {
-    heights: Vec<Vec<u32>>,
+pub struct Input {
+    heights: Grid<u32>,
 }
 
 impl Input {
-    pub fn at(&self, pos: (usize, usize)) -> u32 {
-        self.heights[pos.0][pos.1]
+    pub fn at(&self, pos: Pos) -> u32 {
+        self.heights[pos]
     }
 
-    pub fn min_around(&self, pos: (usize, usize)) -> u32 {
-        *self.heights_around(pos)
-            .iter()
+    pub fn min_around(&self, pos: Pos) -> u32 {
+        self.heights.neighbors4(pos)
+            .map(|pos| self.at(pos))
             .min()
             .unwrap()
     }
 
-    fn heights_around(&self, pos: (usize, usize)) -> Vec<u32> {
-        self.around(pos)
-            .iter()
-            .map(|&pos| self.at(pos))
-            .collect::<Vec<_>>()
-    }
-
-    fn around(&self, pos: (usize, usize)) -> Vec<(usize, usize)> {
-        let mut around = vec![];
-        let (row, column) = pos;
-
-        if row > 0 {
-            around.push((row - 1, column));
-        }
-
-        let rows = self.heights.len();
-
-        if row < rows - 1 {
-            around.push((row + 1, column));
-        }
-
-        if column > 0 {
-            around.push((row, column - 1));
-        }
-
-        let columns = self.heights[0].len();
-
-        if column < columns - 1 {
-            around.push((row, column + 1));
-        }
-
-        around
-    }
-
-    pub fn low_points(&self) -> Vec<(usize, usize)> {
+    pub fn low_points(&self) -> Vec<Pos> {
         self.heights
             .iter()
-            .enumerate()
-            .flat_map(|(row, line)| line.iter()
-                .enumerate()
-                .filter_map(move |(column, &height)| {
-                    let pos = (row, column);
-
-                    if self.min_around(pos) > height {
-                        Some(pos)
-                    } else {
-                        None
-                    }
-                })
-            )
+            .filter_map(|(pos, &height)| {
+                if self.min_around(pos) > height {
+                    Some(pos)
+                } else {
+                    None
+                }
+            })
             .collect::<Vec<_>>()
     }
 
-    fn basin(&self, pos: (usize, usize)) -> usize {
-        let mut basin: HashSet<(usize, usize)> = HashSet::from([pos]);
+    fn basin(&self, pos: Pos) -> usize {
+        let mut basin: HashSet<Pos> = HashSet::from([pos]);
 
         let mut out = basin.clone();
         while !out.is_empty() {
             out = out
                 .into_iter()
-                .flat_map(|pos| self.around(pos))
+                .flat_map(|pos| self.heights.neighbors4(pos))
                 .filter(|&pos| self.at(pos) != 9 && !basin.contains(&pos))
                 .collect();
 
@@ -97,15 +59,10 @@ impl Input {
 
 #[allow(unused_variables)]
 #[aoc_generator(day9)]
-fn input_generator(input: &str) -> Input {
-    Input {
-        heights: input.lines()
-            .map(|line| line.chars()
-                .map(|c| c.to_digit(10).unwrap())
-                .collect::<Vec<_>>(),
-            )
-            .collect::<Vec<_>>(),
-    }
+fn input_generator(input: &str) -> Result<Input, ParseError> {
+    Ok(Input {
+        heights: Grid::digits(9, input)?,
+    })
 }
 
 #[allow(unused_variables)]
@@ -114,7 +71,7 @@ fn input_generator(input: &str) -> Input {
 fn part1(input: &Input) -> u32 {
     input.low_points()
         .iter()
-        .map(|(row, column)| input.heights[*row][*column] + 1)
+        .map(|&pos| input.at(pos) + 1)
         .sum()
 }
 
@@ -129,39 +86,59 @@ fn part2(input: &Input) -> usize {
     sizes.iter().rev().take(3).product()
 }
 
-#[allow(unreachable_code)]
-#[cfg(test)]
-mod tests {
-    use super::*;
+pub struct Day9;
 
-    fn exemple_raw_input() -> &'static str {
-        "2199943210
+impl Puzzle for Day9 {
+    const DAY: u8 = 9;
+    const EXAMPLE: &'static str = "2199943210
 3987894921
 9856789892
 8767896789
-9899965678"
+9899965678";
+    const EXAMPLE_ANSWERS: [Option<Answer>; 2] = [Some(Answer::Number(15)), Some(Answer::Number(1134))];
+
+    type Input = Input;
+    type Output1 = u32;
+    type Output2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
     }
+}
+
+#[allow(unreachable_code)]
+#[cfg(test)]
+mod tests {
+    use super::*;
 
     #[test]
     fn part2_provided_exemple() {
-        assert_eq!(part2(&input_generator(exemple_raw_input())), 1134);
+        assert_eq!(part2(&input_generator(Day9::EXAMPLE).unwrap()), 1134);
     }
 
     #[test]
     fn part1_provided_exemple() {
-        assert_eq!(part1(&input_generator(exemple_raw_input())), 15);
+        assert_eq!(part1(&input_generator(Day9::EXAMPLE).unwrap()), 15);
     }
 
     #[test]
     fn part1_provided_exemple_input() {
-        assert_eq!(input_generator(exemple_raw_input()), Input {
-            heights: vec![
-                vec![2, 1, 9, 9, 9, 4, 3, 2, 1, 0],
-                vec![3, 9, 8, 7, 8, 9, 4, 9, 2, 1],
-                vec![9, 8, 5, 6, 7, 8, 9, 8, 9, 2],
-                vec![8, 7, 6, 7, 8, 9, 6, 7, 8, 9],
-                vec![9, 8, 9, 9, 9, 6, 5, 6, 7, 8],
-            ],
+        assert_eq!(input_generator(Day9::EXAMPLE).unwrap(), Input {
+            heights: Grid::new(10, vec![
+                2, 1, 9, 9, 9, 4, 3, 2, 1, 0,
+                3, 9, 8, 7, 8, 9, 4, 9, 2, 1,
+                9, 8, 5, 6, 7, 8, 9, 8, 9, 2,
+                8, 7, 6, 7, 8, 9, 6, 7, 8, 9,
+                9, 8, 9, 9, 9, 6, 5, 6, 7, 8,
+            ]),
         });
     }
 }
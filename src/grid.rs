@@ -0,0 +1,282 @@
+use std::{fmt::Display, ops::{Index, IndexMut}};
+
+use crate::parse::{self, ParseError};
+
+// (row, column)
+pub type Pos = (usize, usize);
+
+const NEIGHBORS_4: [(isize, isize); 4] = [(-1, 0), (0, -1), (0, 1), (1, 0)];
+
+const NEIGHBORS_8: [(isize, isize); 8] = [
+    (-1, -1), (-1, 0), (-1, 1),
+    (0, -1), (0, 1),
+    (1, -1), (1, 0), (1, 1),
+];
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T> Grid<T> {
+    // cells are given row by row
+    pub fn new(width: usize, cells: Vec<T>) -> Self {
+        assert!(width > 0, "a grid must have at least one column");
+        assert_eq!(cells.len() % width, 0, "all the rows of a grid must have {} cells", width);
+
+        Self {
+            height: cells.len() / width,
+            cells,
+            width,
+        }
+    }
+
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(Pos) -> T) -> Self {
+        Self::new(
+            width,
+            (0..height)
+                .flat_map(|row| (0..width).map(move |column| (row, column)))
+                .map(&mut f)
+                .collect(),
+        )
+    }
+
+    // parses a map with one char per cell, `cell` returns None on unexpected chars
+    pub fn parse(day: u8, input: &str, expected: &str, cell: impl Fn(char) -> Option<T>) -> Result<Self, ParseError> {
+        let rows = parse::lines(input)
+            .map(|(n, line)| line.chars()
+                .enumerate()
+                .map(|(column, c)| cell(c).ok_or_else(|| ParseError::new(day, n, column + 1, expected)))
+                .collect::<Result<Vec<_>, _>>()
+            )
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let width = rows.first().map_or(0, |row| row.len());
+
+        if width == 0 {
+            return Err(ParseError::new(day, 1, 1, expected));
+        }
+
+        if let Some(n) = rows.iter().position(|row| row.len() != width) {
+            let column = std::cmp::min(rows[n].len(), width) + 1;
+
+            return Err(ParseError::new(day, n + 1, column, format!("{} cells per line", width)));
+        }
+
+        Ok(Self::new(width, rows.into_iter().flatten().collect()))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn get(&self, (row, column): Pos) -> Option<&T> {
+        if row < self.height && column < self.width {
+            self.cells.get(row * self.width + column)
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, (row, column): Pos) -> Option<&mut T> {
+        if row < self.height && column < self.width {
+            self.cells.get_mut(row * self.width + column)
+        } else {
+            None
+        }
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let width = self.width;
+
+        (0..self.cells.len()).map(move |i| (i / width, i % width))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width)
+    }
+
+    pub fn column(&self, column: usize) -> impl Iterator<Item = &T> {
+        assert!(column < self.width, "column {} is out of the grid", column);
+
+        self.cells.iter().skip(column).step_by(self.width)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(move |column| self.column(column))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            cells: self.cells.iter().map(f).collect(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    // the position `delta` away from `pos`, if it's still in the grid
+    pub fn offset(&self, (row, column): Pos, (d_row, d_column): (isize, isize)) -> Option<Pos> {
+        let row = row.checked_add_signed(d_row).filter(|&row| row < self.height)?;
+        let column = column.checked_add_signed(d_column).filter(|&column| column < self.width)?;
+
+        Some((row, column))
+    }
+
+    // the position `delta` away from `pos`, going out of one edge of the grid
+    // comes back on the opposite edge
+    pub fn wrapping_offset(&self, (row, column): Pos, (d_row, d_column): (isize, isize)) -> Pos {
+        (
+            (row as isize + d_row).rem_euclid(self.height as isize) as usize,
+            (column as isize + d_column).rem_euclid(self.width as isize) as usize,
+        )
+    }
+
+    pub fn neighbors4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        NEIGHBORS_4.iter().filter_map(move |&delta| self.offset(pos, delta))
+    }
+
+    pub fn neighbors8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        NEIGHBORS_8.iter().filter_map(move |&delta| self.offset(pos, delta))
+    }
+
+    pub fn wrapping_neighbors4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        NEIGHBORS_4.iter().map(move |&delta| self.wrapping_offset(pos, delta))
+    }
+
+    pub fn wrapping_neighbors8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        NEIGHBORS_8.iter().map(move |&delta| self.wrapping_offset(pos, delta))
+    }
+}
+
+impl Grid<u32> {
+    pub fn digits(day: u8, input: &str) -> Result<Self, ParseError> {
+        Self::parse(day, input, "a digit", |c| c.to_digit(10))
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (row, column): Pos) -> &Self::Output {
+        assert!(row < self.height && column < self.width, "{:?} is out of the grid", (row, column));
+
+        &self.cells[row * self.width + column]
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, (row, column): Pos) -> &mut Self::Output {
+        assert!(row < self.height && column < self.width, "{:?} is out of the grid", (row, column));
+
+        &mut self.cells[row * self.width + column]
+    }
+}
+
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.rows()
+            .map(|row| row.iter().map(|cell| cell.to_string()).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+}
+
+#[test]
+fn test_parse() {
+    assert_eq!(Grid::digits(9, "219\n398"), Ok(Grid::new(3, vec![2, 1, 9, 3, 9, 8])));
+    assert_eq!(Grid::digits(9, "21\n3 9"), Err(ParseError::new(9, 2, 2, "a digit")));
+    assert_eq!(Grid::digits(9, "219\n39"), Err(ParseError::new(9, 2, 3, "3 cells per line")));
+    assert_eq!(Grid::digits(9, ""), Err(ParseError::new(9, 1, 1, "a digit")));
+
+    let grid = Grid::parse(20, "#.\n.#", "a pixel", |c| match c {
+        '#' => Some(true),
+        '.' => Some(false),
+        _ => None,
+    });
+
+    assert_eq!(grid, Ok(Grid::new(2, vec![true, false, false, true])));
+}
+
+#[test]
+fn test_views() {
+    let grid = Grid::digits(0, "123\n456").unwrap();
+
+    assert_eq!(grid.width(), 3);
+    assert_eq!(grid.height(), 2);
+    assert_eq!(grid[(1, 0)], 4);
+    assert_eq!(grid.get((2, 0)), None);
+    assert_eq!(grid.get((0, 3)), None);
+    assert_eq!(grid.row(1), [4, 5, 6]);
+    assert_eq!(grid.column(2).collect::<Vec<_>>(), [&3, &6]);
+    assert_eq!(grid.columns().map(|c| c.sum::<u32>()).collect::<Vec<_>>(), [5, 7, 9]);
+    assert_eq!(grid.positions().collect::<Vec<_>>(), [(0, 0), (0, 1), (0, 2), (1, 0), (1, 1), (1, 2)]);
+    assert_eq!(format!("{}", grid.map(|n| n * 2)), "246\n81012");
+}
+
+#[test]
+fn test_neighbors() {
+    let grid = Grid::new(10, vec![0; 100]);
+
+    assert_eq!(grid.neighbors8((1, 1)).collect::<Vec<_>>(), vec![
+        (0, 0),
+        (0, 1),
+        (0, 2),
+        (1, 0),
+        (1, 2),
+        (2, 0),
+        (2, 1),
+        (2, 2),
+    ]);
+    assert_eq!(grid.neighbors8((0, 0)).collect::<Vec<_>>(), vec![
+        (0, 1),
+        (1, 0),
+        (1, 1),
+    ]);
+    assert_eq!(grid.neighbors8((9, 9)).collect::<Vec<_>>(), vec![
+        (8, 8),
+        (8, 9),
+        (9, 8),
+    ]);
+
+    assert_eq!(grid.neighbors4((0, 5)).collect::<Vec<_>>(), vec![(0, 4), (0, 6), (1, 5)]);
+    assert_eq!(grid.neighbors4((5, 9)).collect::<Vec<_>>(), vec![(4, 9), (5, 8), (6, 9)]);
+}
+
+#[test]
+fn test_wrapping_neighbors() {
+    let grid = Grid::new(3, vec![0; 6]);
+
+    assert_eq!(grid.wrapping_offset((1, 2), (0, 1)), (1, 0));
+    assert_eq!(grid.wrapping_offset((1, 2), (1, 0)), (0, 2));
+    assert_eq!(grid.wrapping_offset((0, 0), (-1, -1)), (1, 2));
+    assert_eq!(grid.offset((1, 2), (0, 1)), None);
+
+    assert_eq!(grid.wrapping_neighbors4((0, 0)).collect::<Vec<_>>(), vec![(1, 0), (0, 2), (0, 1), (1, 0)]);
+    assert_eq!(grid.wrapping_neighbors8((0, 0)).count(), 8);
+}
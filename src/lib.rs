@@ -3,6 +3,15 @@ extern crate aoc_runner;
 #[macro_use]
 extern crate aoc_runner_derive;
 
+pub mod grid;
+pub mod parse;
+pub mod puzzle;
+pub mod regression;
+pub mod search;
+pub mod solve;
+
+pub use solve::{solve, Answer, SolveError};
+
 pub mod day1;
 pub mod day2;
 pub mod day3;
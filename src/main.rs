@@ -1,7 +1,406 @@
-extern crate advent_of_code_2021;
-extern crate aoc_runner_derive;
-extern crate aoc_runner;
+use std::{env, fs, io::{self, BufRead, Read, Write}, path::Path, process::ExitCode, time::{Duration, Instant}};
 
-use aoc_runner_derive::aoc_main;
+use advent_of_code_2021::{
+    day24::{Breakpoint, Day24, Debugger, Stop},
+    parse,
+    puzzle::{visit_all, Puzzle, PuzzleVisitor},
+    regression::{self, DayStatus, Outcome},
+    solve, Answer,
+};
 
-aoc_main! { lib = advent_of_code_2021 }
+const USAGE: &str = "usage: advent-of-code-2021 [--json] <command>
+
+commands:
+    run --day N --part P [--input PATH|-]   solve one part, from input/2021/dayN.txt by default or - for stdin
+    all [--input-dir DIR]                   solve every day that has an input in DIR (input/2021 by default)
+    list                                    show the implemented days and parts
+    trace --inputs N,N… [--break I|inp]… [--input PATH|-]
+                                            run a day 24 ALU program and show the registers after each
+                                            instruction, marking the breakpoints (instruction I or each inp)
+    debug --inputs N,N… [--break I|inp]… [--input PATH|-]
+                                            same, step by step: (s)tep, (c)ontinue, (r)egisters,
+                                            (b)reak I|inp, (q)uit
+
+--json prints the results as JSON";
+
+const INPUT_DIR: &str = "input/2021";
+
+struct Options {
+    json: bool,
+    command: String,
+    args: Vec<(String, String)>,
+}
+
+impl Options {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut json = false;
+        let mut command = None;
+        let mut options = vec![];
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--json" => json = true,
+                "-h" | "--help" => return Err(USAGE.to_string()),
+                _ if arg.starts_with("--") => match args.next() {
+                    Some(value) => options.push((arg[2..].to_string(), value)),
+                    None => return Err(format!("missing value for {}", arg)),
+                },
+                _ if command.is_none() => command = Some(arg),
+                _ => return Err(format!("unexpected argument: {}", arg)),
+            }
+        }
+
+        Ok(Self {
+            json,
+            command: command.ok_or_else(|| USAGE.to_string())?,
+            args: options,
+        })
+    }
+
+    fn get(&self, name: &str) -> Option<&str> {
+        self.args.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str())
+    }
+
+    // options that can be given more than once
+    fn get_all<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a str> {
+        self.args.iter().filter(move |(key, _)| key == name).map(|(_, value)| value.as_str())
+    }
+
+    fn number(&self, name: &str) -> Result<u8, String> {
+        self.get(name)
+            .ok_or_else(|| format!("--{} is required", name))?
+            .parse()
+            .map_err(|_| format!("--{} must be a number", name))
+    }
+
+    fn check(&self, allowed: &[&str]) -> Result<(), String> {
+        match self.args.iter().find(|(key, _)| !allowed.contains(&key.as_str())) {
+            Some((key, _)) => Err(format!("unknown option for {}: --{}", self.command, key)),
+            None => Ok(()),
+        }
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut json = String::from('"');
+
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            c if (c as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+
+    json.push('"');
+
+    json
+}
+
+fn json_answer(answer: &Answer) -> String {
+    match answer {
+        Answer::Number(n) => n.to_string(),
+        Answer::Text(s) => json_string(s),
+    }
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+// from --input, input/2021/dayN.txt by default or - for stdin
+fn read_input(options: &Options, day: u8) -> Result<String, String> {
+    Ok(match options.get("input") {
+        Some("-") => {
+            let mut input = String::new();
+
+            io::stdin().read_to_string(&mut input).map_err(|e| format!("can't read stdin: {}", e))?;
+
+            input
+        },
+        Some(path) => fs::read_to_string(path).map_err(|e| format!("can't read {}: {}", path, e))?,
+        None => {
+            let path = regression::input_path(Path::new(INPUT_DIR), day);
+
+            fs::read_to_string(&path).map_err(|e| format!("can't read {}: {}", path.display(), e))?
+        },
+    })
+}
+
+fn run(options: &Options) -> Result<(), String> {
+    options.check(&["day", "part", "input"])?;
+
+    let day = options.number("day")?;
+    let part = options.number("part")?;
+    let input = read_input(options, day)?;
+
+    let start = Instant::now();
+    let answer = solve(day, part, &input).map_err(|e| e.to_string())?;
+    let elapsed = start.elapsed();
+
+    if options.json {
+        println!(
+            "{{\"day\":{},\"part\":{},\"answer\":{},\"elapsed_ms\":{}}}",
+            day, part, json_answer(&answer), millis(elapsed),
+        );
+    } else {
+        println!("{}", answer);
+    }
+
+    Ok(())
+}
+
+fn all(options: &Options) -> Result<(), String> {
+    options.check(&["input-dir"])?;
+
+    let dir = options.get("input-dir").unwrap_or(INPUT_DIR);
+    let report = regression::check(dir).map_err(|e| format!("can't check {}: {}", dir, e))?;
+
+    if !options.json {
+        print!("{}", report);
+
+        return if report.is_ok() { Ok(()) } else { Err("some answers are wrong".to_string()) };
+    }
+
+    let days = report.days.iter()
+        .map(|day| match &day.status {
+            DayStatus::NoInput => format!("{{\"day\":{},\"status\":\"no input\"}}", day.day),
+            DayStatus::InvalidInput(e) => format!(
+                "{{\"day\":{},\"status\":\"invalid input\",\"error\":{}}}",
+                day.day, json_string(&e.to_string()),
+            ),
+            DayStatus::Solved { parsing, parts } => format!(
+                "{{\"day\":{},\"status\":\"solved\",\"parsing_ms\":{},\"parts\":[{}]}}",
+                day.day,
+                millis(*parsing),
+                parts.iter()
+                    .map(|part| format!(
+                        "{{\"part\":{},\"answer\":{},\"expected\":{},\"ok\":{}{},\"elapsed_ms\":{}}}",
+                        part.part,
+                        part.answer.as_ref().map_or("null".to_string(), json_answer),
+                        match &part.outcome {
+                            Outcome::Mismatch { expected } => json_string(expected),
+                            Outcome::Match => part.answer.as_ref().map_or("null".to_string(), json_answer),
+                            Outcome::Unknown | Outcome::Failed { .. } => "null".to_string(),
+                        },
+                        match part.outcome {
+                            Outcome::Match => "true",
+                            Outcome::Mismatch { .. } | Outcome::Failed { .. } => "false",
+                            Outcome::Unknown => "null",
+                        },
+                        match &part.outcome {
+                            Outcome::Failed { reason } => format!(",\"error\":{}", json_string(reason)),
+                            _ => String::new(),
+                        },
+                        millis(part.elapsed),
+                    ))
+                    .collect::<Vec<_>>()
+                    .join(","),
+            ),
+        })
+        .collect::<Vec<_>>();
+
+    println!("[{}]", days.join(","));
+
+    if report.is_ok() { Ok(()) } else { Err("some answers are wrong".to_string()) }
+}
+
+struct Implemented {
+    days: Vec<(u8, Vec<u8>)>,
+}
+
+impl PuzzleVisitor for Implemented {
+    fn visit<P: Puzzle>(&mut self) {
+        self.days.push((P::DAY, (1..=2).filter(|&part| P::solved(part)).collect()));
+    }
+}
+
+fn list(options: &Options) -> Result<(), String> {
+    options.check(&[])?;
+
+    let mut implemented = Implemented { days: vec![] };
+
+    visit_all(&mut implemented);
+
+    let days = implemented.days.iter()
+        .map(|(day, parts)| (day, parts.iter().map(|part| part.to_string()).collect::<Vec<_>>()));
+
+    if options.json {
+        let days = days
+            .map(|(day, parts)| format!("{{\"day\":{},\"parts\":[{}]}}", day, parts.join(",")))
+            .collect::<Vec<_>>();
+
+        println!("[{}]", days.join(","));
+    } else {
+        for (day, parts) in days {
+            println!("day {:>2}: part {}", day, parts.join(", part "));
+        }
+    }
+
+    Ok(())
+}
+
+fn breakpoint(value: &str) -> Result<Breakpoint, String> {
+    match value {
+        "inp" => Ok(Breakpoint::Inp),
+        index => index.parse()
+            .map(Breakpoint::Instruction)
+            .map_err(|_| format!("breakpoints are an instruction index or inp, not {}", index)),
+    }
+}
+
+fn debugger(options: &Options) -> Result<Debugger, String> {
+    options.check(&["inputs", "break", "input"])?;
+
+    let program = Day24::parse(parse::trim_input(&read_input(options, Day24::DAY)?)).map_err(|e| e.to_string())?;
+
+    let inputs = options.get("inputs")
+        .ok_or("--inputs is required")?
+        .split(',')
+        .map(|n| n.trim().parse().map_err(|_| format!("--inputs must be numbers separated by commas, not {}", n)))
+        .collect::<Result<Vec<i32>, _>>()?;
+
+    let mut debugger = program.debugger(inputs);
+
+    for value in options.get_all("break") {
+        debugger.break_at(breakpoint(value)?);
+    }
+
+    Ok(debugger)
+}
+
+fn stop_message(stop: Stop, debugger: &Debugger) -> String {
+    match stop {
+        Stop::Breakpoint(index) => format!(
+            "-- breakpoint before {} `{}`",
+            index,
+            debugger.next_instruction().unwrap_or_default(),
+        ),
+        Stop::Step => format!("-- before {} `{}`", debugger.position(), debugger.next_instruction().unwrap_or_default()),
+        Stop::End => "-- end".to_string(),
+        Stop::Fault(e) => format!("-- {}", e),
+    }
+}
+
+fn trace(options: &Options) -> Result<(), String> {
+    let mut debugger = debugger(options)?;
+    let mut printed = 0;
+
+    let stop = loop {
+        let stop = debugger.resume();
+
+        if !options.json {
+            print!("{}", debugger.trace()[printed..].iter().map(|step| format!("{}\n", step)).collect::<String>());
+            println!("{}", stop_message(stop, &debugger));
+        }
+
+        printed = debugger.trace().len();
+
+        if !matches!(stop, Stop::Breakpoint(_)) {
+            break stop;
+        }
+    };
+
+    if options.json {
+        let steps = debugger.trace().iter()
+            .map(|step| format!(
+                "{{\"index\":{},\"instruction\":{},\"registers\":[{}]}}",
+                step.index,
+                json_string(&step.instruction()),
+                step.registers.iter().map(|register| register.to_string()).collect::<Vec<_>>().join(","),
+            ))
+            .collect::<Vec<_>>();
+
+        println!("[{}]", steps.join(","));
+    }
+
+    match stop {
+        Stop::Fault(e) => Err(format!("the ALU crashed at {}", e)),
+        _ => Ok(()),
+    }
+}
+
+fn debug(options: &Options) -> Result<(), String> {
+    let mut debugger = debugger(options)?;
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+
+    println!("-- before 0 `{}`", debugger.next_instruction().unwrap_or_default());
+
+    loop {
+        print!("> ");
+        io::stdout().flush().map_err(|e| e.to_string())?;
+
+        let line = match lines.next() {
+            Some(line) => line.map_err(|e| format!("can't read stdin: {}", e))?,
+            None => return Ok(()),
+        };
+
+        let printed = debugger.trace().len();
+
+        let stop = match line.split_whitespace().collect::<Vec<_>>()[..] {
+            ["s" | "step"] => debugger.step(),
+            ["c" | "continue"] => debugger.resume(),
+            ["r" | "registers"] => {
+                let [w, x, y, z] = debugger.registers();
+
+                println!("w={} x={} y={} z={}", w, x, y, z);
+
+                continue;
+            },
+            ["b" | "break", value] => {
+                match breakpoint(value) {
+                    Ok(breakpoint) => debugger.break_at(breakpoint),
+                    Err(e) => println!("{}", e),
+                }
+
+                continue;
+            },
+            ["q" | "quit"] => return Ok(()),
+            [] => continue,
+            _ => {
+                println!("commands: (s)tep, (c)ontinue, (r)egisters, (b)reak I|inp, (q)uit");
+
+                continue;
+            },
+        };
+
+        print!("{}", debugger.trace()[printed..].iter().map(|step| format!("{}\n", step)).collect::<String>());
+        println!("{}", stop_message(stop, &debugger));
+    }
+}
+
+fn main() -> ExitCode {
+    let options = match Options::parse(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}", e);
+
+            return ExitCode::from(2);
+        },
+    };
+
+    let result = match options.command.as_str() {
+        "run" => run(&options),
+        "all" => all(&options),
+        "list" => list(&options),
+        "trace" => trace(&options),
+        "debug" => debug(&options),
+        command => {
+            eprintln!("unknown command: {}\n\n{}", command, USAGE);
+
+            return ExitCode::from(2);
+        },
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+
+            ExitCode::FAILURE
+        },
+    }
+}
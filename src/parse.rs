@@ -0,0 +1,83 @@
+use std::{error::Error, fmt::Display, str::FromStr};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ParseError {
+    pub day: u8,
+    pub line: usize,
+    pub column: usize,
+    pub expected: String,
+}
+
+impl ParseError {
+    pub fn new(day: u8, line: usize, column: usize, expected: impl Into<String>) -> Self {
+        Self {
+            day,
+            line,
+            column,
+            expected: expected.into(),
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "day {} input, line {}, column {}: expected {}",
+            self.day, self.line, self.column, self.expected,
+        )
+    }
+}
+
+impl Error for ParseError {}
+
+// lines and columns are counted from 1, like in text editors
+pub fn lines(input: &str) -> impl Iterator<Item = (usize, &str)> {
+    input.lines().enumerate().map(|(n, line)| (n + 1, line))
+}
+
+// input files end with a new line, like aoc-runner we don't give it to the generators
+// (`lines` takes care of the `\r` of the other lines, not of the last one)
+pub fn trim_input(input: &str) -> &str {
+    input.trim_end_matches(['\n', '\r'])
+}
+
+// column of `part` in `line`, `part` must be a slice of `line`
+// (eg: from `split` or `trim`)
+pub fn column(line: &str, part: &str) -> usize {
+    part.as_ptr() as usize - line.as_ptr() as usize + 1
+}
+
+pub fn parse<T: FromStr>(day: u8, line: (usize, &str), part: &str, expected: &str) -> Result<T, ParseError> {
+    part.parse::<T>()
+        .map_err(|_| ParseError::new(day, line.0, column(line.1, part), expected))
+}
+
+#[test]
+fn test_parse() {
+    let line = "12,a4";
+    let (a, b) = line.split_once(',').unwrap();
+
+    assert_eq!(parse::<u32>(1, (3, line), a, "a number"), Ok(12));
+    assert_eq!(parse::<u32>(1, (3, line), b, "a number"), Err(ParseError {
+        day: 1,
+        line: 3,
+        column: 4,
+        expected: "a number".to_string(),
+    }));
+}
+
+#[test]
+fn test_trim_input() {
+    assert_eq!(trim_input("1\n2\n"), "1\n2");
+    assert_eq!(trim_input("1\r\n2\r\n"), "1\r\n2");
+    assert_eq!(trim_input("1\n2 \n\n"), "1\n2 ");
+}
+
+#[test]
+fn test_display() {
+    assert_eq!(
+        format!("{}", ParseError::new(16, 1, 42, "an hexadecimal digit")),
+        "day 16 input, line 1, column 42: expected an hexadecimal digit",
+    );
+}
@@ -0,0 +1,104 @@
+use crate::{parse::ParseError, solve::{Answer, IntoAnswer}};
+
+pub trait Puzzle {
+    const DAY: u8;
+
+    // the example given in the puzzle description
+    const EXAMPLE: &'static str;
+
+    // answers to `EXAMPLE` for each part, None when we don't know it
+    const EXAMPLE_ANSWERS: [Option<Answer>; 2];
+
+    type Input;
+    type Output1: IntoAnswer;
+    type Output2: IntoAnswer;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError>;
+
+    fn part1(input: &Self::Input) -> Self::Output1;
+
+    fn part2(input: &Self::Input) -> Self::Output2;
+
+    // whether `part` has a solution, calling an unsolved part panics
+    fn solved(part: u8) -> bool {
+        part == 1 || part == 2
+    }
+}
+
+// lets generic code go through all the days, since we can't loop over types
+pub trait PuzzleVisitor {
+    fn visit<P: Puzzle>(&mut self);
+}
+
+pub fn visit_all(visitor: &mut impl PuzzleVisitor) {
+    use crate::*;
+
+    visitor.visit::<day1::Day1>();
+    visitor.visit::<day2::Day2>();
+    visitor.visit::<day3::Day3>();
+    visitor.visit::<day4::Day4>();
+    visitor.visit::<day5::Day5>();
+    visitor.visit::<day6::Day6>();
+    visitor.visit::<day7::Day7>();
+    visitor.visit::<day8::Day8>();
+    visitor.visit::<day9::Day9>();
+    visitor.visit::<day10::Day10>();
+    visitor.visit::<day11::Day11>();
+    visitor.visit::<day12::Day12>();
+    visitor.visit::<day13::Day13>();
+    visitor.visit::<day14::Day14>();
+    visitor.visit::<day15::Day15>();
+    visitor.visit::<day16::Day16>();
+    visitor.visit::<day17::Day17>();
+    visitor.visit::<day18::Day18>();
+    visitor.visit::<day19::Day19>();
+    visitor.visit::<day20::Day20>();
+    visitor.visit::<day21::Day21>();
+    visitor.visit::<day22::Day22>();
+    visitor.visit::<day23::Day23>();
+    visitor.visit::<day24::Day24>();
+    visitor.visit::<day25::Day25>();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Examples {
+        days: Vec<u8>,
+        mismatches: Vec<String>,
+    }
+
+    impl PuzzleVisitor for Examples {
+        fn visit<P: Puzzle>(&mut self) {
+            self.days.push(P::DAY);
+
+            let input = P::parse(P::EXAMPLE).unwrap();
+
+            let answers = [
+                P::solved(1).then(|| P::part1(&input).into_answer()),
+                P::solved(2).then(|| P::part2(&input).into_answer()),
+            ];
+
+            for (part, (expected, answer)) in P::EXAMPLE_ANSWERS.iter().zip(answers).enumerate() {
+                match (expected, answer) {
+                    (Some(expected), Some(Ok(answer))) if *expected != answer => {
+                        self.mismatches.push(format!("day {} part {}: expected {}, got {}", P::DAY, part + 1, expected, answer));
+                    },
+                    (_, Some(Err(reason))) => self.mismatches.push(format!("day {} part {}: {}", P::DAY, part + 1, reason)),
+                    _ => {},
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_examples() {
+        let mut examples = Examples { days: vec![], mismatches: vec![] };
+
+        visit_all(&mut examples);
+
+        assert_eq!(examples.days, (1..=25).collect::<Vec<_>>());
+        assert_eq!(examples.mismatches, Vec::<String>::new());
+    }
+}
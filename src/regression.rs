@@ -0,0 +1,287 @@
+use std::{collections::HashMap, fmt::Display, fs, io, path::{Path, PathBuf}, time::{Duration, Instant}};
+
+use crate::{parse::{self, ParseError}, puzzle::{visit_all, Puzzle, PuzzleVisitor}, solve::{Answer, IntoAnswer}};
+
+// expected answers, one per line: `<day> <part> <answer>`
+// `\n` stands for a new line in multi-line answers (eg: day 13 part 2)
+// empty lines and lines starting with # are ignored
+#[derive(Debug, PartialEq, Default)]
+pub struct Manifest {
+    answers: HashMap<(u8, u8), String>,
+}
+
+impl Manifest {
+    pub fn parse(input: &str) -> io::Result<Self> {
+        let mut answers = HashMap::new();
+
+        for (n, line) in parse::lines(input) {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut parts = line.splitn(3, ' ');
+
+            match (
+                parts.next().and_then(|day| day.parse().ok()),
+                parts.next().and_then(|part| part.parse().ok()),
+                parts.next(),
+            ) {
+                (Some(day), Some(part), Some(answer)) => answers.insert((day, part), answer.replace("\\n", "\n")),
+                _ => return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("answers manifest, line {}: expected <day> <part> <answer>", n),
+                )),
+            };
+        }
+
+        Ok(Self { answers })
+    }
+
+    pub fn get(&self, day: u8, part: u8) -> Option<&str> {
+        self.answers.get(&(day, part)).map(String::as_str)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Outcome {
+    Match,
+    Mismatch { expected: String },
+    // the manifest has no answer for this part
+    Unknown,
+    // the part has no answer for this input
+    Failed { reason: String },
+}
+
+#[derive(Debug)]
+pub struct PartReport {
+    pub part: u8,
+    // none when the part failed
+    pub answer: Option<Answer>,
+    pub outcome: Outcome,
+    pub elapsed: Duration,
+}
+
+#[derive(Debug)]
+pub enum DayStatus {
+    NoInput,
+    InvalidInput(ParseError),
+    Solved { parsing: Duration, parts: Vec<PartReport> },
+}
+
+#[derive(Debug)]
+pub struct DayReport {
+    pub day: u8,
+    pub status: DayStatus,
+}
+
+#[derive(Debug)]
+pub struct Report {
+    pub days: Vec<DayReport>,
+}
+
+impl Report {
+    pub fn mismatches(&self) -> impl Iterator<Item = (u8, &PartReport)> {
+        self.days.iter()
+            .flat_map(|day| match &day.status {
+                DayStatus::Solved { parts, .. } => parts.iter().map(|part| (day.day, part)).collect(),
+                _ => vec![],
+            })
+            .filter(|(_, part)| matches!(part.outcome, Outcome::Mismatch { .. } | Outcome::Failed { .. }))
+    }
+
+    // no wrong or missing answer and no input we can't parse
+    pub fn is_ok(&self) -> bool {
+        self.mismatches().next().is_none()
+            && !self.days.iter().any(|day| matches!(day.status, DayStatus::InvalidInput(_)))
+    }
+}
+
+impl Display for Report {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for day in &self.days {
+            match &day.status {
+                DayStatus::NoInput => writeln!(f, "day {:>2}: no input", day.day)?,
+                DayStatus::InvalidInput(e) => writeln!(f, "day {:>2}: invalid input, {}", day.day, e)?,
+                DayStatus::Solved { parsing, parts } => {
+                    writeln!(f, "day {:>2}: parsed in {:?}", day.day, parsing)?;
+
+                    for part in parts {
+                        let answer = part.answer.as_ref().map_or(String::new(), |answer| answer.to_string().replace('\n', "\\n"));
+
+                        match &part.outcome {
+                            Outcome::Match => writeln!(f, "  part {}: {} ok ({:?})", part.part, answer, part.elapsed)?,
+                            Outcome::Unknown => writeln!(f, "  part {}: {} ({:?})", part.part, answer, part.elapsed)?,
+                            Outcome::Mismatch { expected } => writeln!(
+                                f,
+                                "  part {}: {} MISMATCH, expected {} ({:?})",
+                                part.part,
+                                answer,
+                                expected.replace('\n', "\\n"),
+                                part.elapsed,
+                            )?,
+                            Outcome::Failed { reason } => writeln!(f, "  part {}: FAILED, {} ({:?})", part.part, reason, part.elapsed)?,
+                        }
+                    }
+                },
+            }
+        }
+
+        Ok(())
+    }
+}
+
+struct Checker<'a> {
+    dir: &'a Path,
+    manifest: &'a Manifest,
+    days: Vec<DayReport>,
+    error: Option<io::Error>,
+}
+
+impl Checker<'_> {
+    fn part<P: Puzzle, O: IntoAnswer>(&self, part: u8, solve: impl FnOnce() -> O) -> PartReport {
+        let start = Instant::now();
+        let answer = solve().into_answer();
+        let elapsed = start.elapsed();
+
+        let outcome = match (&answer, self.manifest.get(P::DAY, part)) {
+            (Err(reason), _) => Outcome::Failed { reason: reason.clone() },
+            (Ok(_), None) => Outcome::Unknown,
+            (Ok(answer), Some(expected)) if answer.to_string() == expected => Outcome::Match,
+            (Ok(_), Some(expected)) => Outcome::Mismatch { expected: expected.to_string() },
+        };
+
+        PartReport { part, answer: answer.ok(), outcome, elapsed }
+    }
+}
+
+impl PuzzleVisitor for Checker<'_> {
+    fn visit<P: Puzzle>(&mut self) {
+        if self.error.is_some() {
+            return;
+        }
+
+        let input = match fs::read_to_string(input_path(self.dir, P::DAY)) {
+            Ok(input) => input,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                self.days.push(DayReport { day: P::DAY, status: DayStatus::NoInput });
+
+                return;
+            },
+            Err(e) => {
+                self.error = Some(e);
+
+                return;
+            },
+        };
+
+        let start = Instant::now();
+
+        let status = match P::parse(parse::trim_input(&input)) {
+            Err(e) => DayStatus::InvalidInput(e),
+            Ok(input) => {
+                let parsing = start.elapsed();
+                let mut parts = vec![];
+
+                if P::solved(1) {
+                    parts.push(self.part::<P, _>(1, || P::part1(&input)));
+                }
+
+                if P::solved(2) {
+                    parts.push(self.part::<P, _>(2, || P::part2(&input)));
+                }
+
+                DayStatus::Solved { parsing, parts }
+            },
+        };
+
+        self.days.push(DayReport { day: P::DAY, status });
+    }
+}
+
+// same layout as aoc-runner: <dir>/dayN.txt
+pub fn input_path(dir: &Path, day: u8) -> PathBuf {
+    dir.join(format!("day{}.txt", day))
+}
+
+// runs every day that has an input in `dir` and compares the answers with `<dir>/answers.txt`
+pub fn check(dir: impl AsRef<Path>) -> io::Result<Report> {
+    let dir = dir.as_ref();
+
+    let manifest = match fs::read_to_string(dir.join("answers.txt")) {
+        Ok(manifest) => Manifest::parse(&manifest)?,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Manifest::default(),
+        Err(e) => return Err(e),
+    };
+
+    let mut checker = Checker { dir, manifest: &manifest, days: vec![], error: None };
+
+    visit_all(&mut checker);
+
+    match checker.error {
+        Some(e) => Err(e),
+        None => Ok(Report { days: checker.days }),
+    }
+}
+
+#[test]
+fn test_manifest() {
+    let manifest = Manifest::parse("# day 1\n1 1 7\n1 2 5\n\n13 2 #.\\n.#").unwrap();
+
+    assert_eq!(manifest.get(1, 1), Some("7"));
+    assert_eq!(manifest.get(1, 2), Some("5"));
+    assert_eq!(manifest.get(13, 2), Some("#.\n.#"));
+    assert_eq!(manifest.get(2, 1), None);
+
+    assert_eq!(
+        Manifest::parse("1 1 7\n1 x 5").unwrap_err().to_string(),
+        "answers manifest, line 2: expected <day> <part> <answer>",
+    );
+}
+
+#[test]
+fn test_check() {
+    let dir = std::env::temp_dir().join(format!("aoc-2021-regression-{}", std::process::id()));
+
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(input_path(&dir, 1), "199\n200\n208\n210\n200\n207\n240\n269\n260\n263\n").unwrap();
+    fs::write(input_path(&dir, 2), "forward x\n").unwrap();
+    fs::write(input_path(&dir, 23), "#######\n#.....#\n##B#A##\n #A#B#\n #####\n").unwrap();
+    fs::write(dir.join("answers.txt"), "1 1 7\n1 2 6\n").unwrap();
+
+    let report = check(&dir);
+
+    fs::remove_dir_all(&dir).unwrap();
+
+    let report = report.unwrap();
+
+    assert_eq!(report.days.len(), 25);
+    assert!(matches!(report.days[2].status, DayStatus::NoInput));
+    assert!(matches!(report.days[1].status, DayStatus::InvalidInput(_)));
+    assert!(!report.is_ok());
+
+    let mismatches = report.mismatches().map(|(day, part)| (day, part.part, &part.outcome)).collect::<Vec<_>>();
+
+    assert_eq!(mismatches, [
+        (1, 2, &Outcome::Mismatch { expected: "6".to_string() }),
+        (23, 2, &Outcome::Failed { reason: "can't unfold the row DCBA in 2 rooms".to_string() }),
+    ]);
+
+    let display = report.to_string();
+
+    assert!(display.starts_with("day  1: parsed in "));
+    assert!(display.contains("  part 1: 7 ok ("));
+    assert!(display.contains("  part 2: 5 MISMATCH, expected 6 ("));
+    assert!(display.contains("day  3: no input\n"));
+    assert!(display.contains("  part 1: 46 ("));
+    assert!(display.contains("  part 2: FAILED, can't unfold the row DCBA in 2 rooms ("));
+}
+
+// checks the answers of the inputs kept in input/2021, days without input are skipped
+#[test]
+fn known_answers() {
+    let report = check(Path::new(env!("CARGO_MANIFEST_DIR")).join("input/2021")).unwrap();
+
+    print!("{}", report);
+
+    assert!(report.is_ok(), "{}", report);
+}
@@ -0,0 +1,202 @@
+use std::{cmp::Reverse, collections::{BinaryHeap, HashMap}, hash::Hash, ops::Add};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Path<S, C> {
+    pub cost: C,
+    // from the start to the goal, both included
+    pub states: Vec<S>,
+}
+
+// uniform-cost search, `successors` yields the states reachable in one step with the cost of that step
+pub fn dijkstra<S, C, I>(
+    start: S,
+    successors: impl FnMut(&S) -> I,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Option<Path<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Add<Output = C> + Default,
+    I: IntoIterator<Item = (S, C)>,
+{
+    astar(start, successors, |_| C::default(), is_goal)
+}
+
+// `heuristic` must never overestimate the cost left to reach a goal, otherwise the path
+// found might not be the cheapest one. the default cost is used as zero
+pub fn astar<S, C, I>(
+    start: S,
+    mut successors: impl FnMut(&S) -> I,
+    heuristic: impl FnMut(&S) -> C,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Option<Path<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Add<Output = C> + Default,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let successors = |state: &S| successors(state).into_iter().map(|(next, cost)| (next, (), cost));
+
+    labeled(start, successors, heuristic, is_goal).map(|(path, _)| path)
+}
+
+// like `dijkstra`, with the step to each state (eg: a move) coming along with it,
+// the steps of the path are given with it, one less than its states
+pub fn dijkstra_labeled<S, L, C, I>(
+    start: S,
+    successors: impl FnMut(&S) -> I,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Option<(Path<S, C>, Vec<L>)>
+where
+    S: Clone + Eq + Hash,
+    L: Clone,
+    C: Copy + Ord + Add<Output = C> + Default,
+    I: IntoIterator<Item = (S, L, C)>,
+{
+    labeled(start, successors, |_| C::default(), is_goal)
+}
+
+fn labeled<S, L, C, I>(
+    start: S,
+    mut successors: impl FnMut(&S) -> I,
+    mut heuristic: impl FnMut(&S) -> C,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Option<(Path<S, C>, Vec<L>)>
+where
+    S: Clone + Eq + Hash,
+    L: Clone,
+    C: Copy + Ord + Add<Output = C> + Default,
+    I: IntoIterator<Item = (S, L, C)>,
+{
+    // states are numbered in discovery order, everything else refers to them by index
+    let mut states = vec![start.clone()];
+    let mut indexes = HashMap::from([(start.clone(), 0)]);
+    // the previous state, and the step from it
+    let mut parents: Vec<Option<(usize, L)>> = vec![None];
+    let mut costs = vec![C::default()];
+
+    let mut queue = BinaryHeap::from([Reverse((heuristic(&start), C::default(), 0))]);
+
+    while let Some(Reverse((_, cost, index))) = queue.pop() {
+        if cost > costs[index] {
+            // we already went through this state with a lower cost
+            continue;
+        }
+
+        if is_goal(&states[index]) {
+            let mut path = vec![states[index].clone()];
+            let mut labels = vec![];
+            let mut current = index;
+
+            while let Some((parent, label)) = &parents[current] {
+                path.push(states[*parent].clone());
+                labels.push(label.clone());
+                current = *parent;
+            }
+
+            path.reverse();
+            labels.reverse();
+
+            return Some((Path { cost, states: path }, labels));
+        }
+
+        for (next, label, step) in successors(&states[index]) {
+            let next_cost = cost + step;
+
+            let next_index = match indexes.get(&next) {
+                Some(&next_index) if costs[next_index] <= next_cost => continue,
+                Some(&next_index) => {
+                    costs[next_index] = next_cost;
+                    parents[next_index] = Some((index, label));
+
+                    next_index
+                },
+                None => {
+                    states.push(next.clone());
+                    indexes.insert(next, states.len() - 1);
+                    parents.push(Some((index, label)));
+                    costs.push(next_cost);
+
+                    states.len() - 1
+                },
+            };
+
+            queue.push(Reverse((next_cost + heuristic(&states[next_index]), next_cost, next_index)));
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a -1-> b -1-> c -1-> d
+    //  \-----5----/ \-1-> e
+    fn graph(node: &char) -> Vec<(char, u32)> {
+        match node {
+            'a' => vec![('b', 1), ('c', 5)],
+            'b' => vec![('c', 1)],
+            'c' => vec![('d', 1), ('e', 1)],
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        assert_eq!(dijkstra('a', graph, |&node| node == 'd'), Some(Path { cost: 3, states: vec!['a', 'b', 'c', 'd'] }));
+        assert_eq!(dijkstra('a', graph, |&node| node == 'a'), Some(Path { cost: 0, states: vec!['a'] }));
+        assert_eq!(dijkstra('b', graph, |&node| node == 'a'), None);
+    }
+
+    #[test]
+    fn test_astar() {
+        // distance from each node to d
+        let heuristic = |node: &char| match node {
+            'a' => 3,
+            'b' => 2,
+            'c' => 1,
+            _ => 0,
+        };
+
+        assert_eq!(
+            astar('a', graph, heuristic, |&node| node == 'd'),
+            Some(Path { cost: 3, states: vec!['a', 'b', 'c', 'd'] }),
+        );
+    }
+
+    #[test]
+    fn test_dijkstra_labeled() {
+        let labeled = |node: &char| graph(node).into_iter().map(|(next, cost)| (next, format!("{}{}", node, next), cost)).collect::<Vec<_>>();
+
+        assert_eq!(
+            dijkstra_labeled('a', labeled, |&node| node == 'e'),
+            Some((Path { cost: 3, states: vec!['a', 'b', 'c', 'e'] }, vec!["ab".to_string(), "bc".to_string(), "ce".to_string()])),
+        );
+        assert_eq!(dijkstra_labeled('a', labeled, |&node| node == 'a'), Some((Path { cost: 0, states: vec!['a'] }, vec![])));
+        assert_eq!(dijkstra_labeled('d', labeled, |&node| node == 'a'), None);
+    }
+
+    #[test]
+    fn test_grid() {
+        // the cheapest way goes around the wall of 9
+        let grid = crate::grid::Grid::digits(0, "1911\n1919\n1119").unwrap();
+        let end = (0, 3);
+
+        let path = dijkstra(
+            (0, 0),
+            |&pos| grid.neighbors4(pos).map(|next| (next, grid[next])).collect::<Vec<_>>(),
+            |&pos| pos == end,
+        ).unwrap();
+
+        assert_eq!(path.cost, 7);
+        assert_eq!(path.states, [(0, 0), (1, 0), (2, 0), (2, 1), (2, 2), (1, 2), (0, 2), (0, 3)]);
+
+        let manhattan = |&(row, column): &(usize, usize)| (row.abs_diff(end.0) + column.abs_diff(end.1)) as u32;
+
+        assert_eq!(
+            astar((0, 0), |&pos| grid.neighbors4(pos).map(|next| (next, grid[next])).collect::<Vec<_>>(), manhattan, |&pos| pos == end),
+            Some(path),
+        );
+    }
+}
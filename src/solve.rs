@@ -0,0 +1,178 @@
+use std::{borrow::Cow, error::Error, fmt::Display};
+
+use crate::{parse::ParseError, puzzle::Puzzle};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Answer {
+    Number(i64),
+    Text(Cow<'static, str>),
+}
+
+macro_rules! number_answer {
+    ($($t:ty),*) => {
+        $(impl From<$t> for Answer {
+            // numbers too large for an i64 are still exact as text
+            fn from(n: $t) -> Self {
+                i64::try_from(n).map_or_else(|_| Answer::Text(n.to_string().into()), Answer::Number)
+            }
+        })*
+    };
+}
+
+number_answer!(u32, u64, usize, i32, isize);
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Text(Cow::Owned(s))
+    }
+}
+
+// what a part returns, parts that can fail on some inputs return a `Result`
+pub trait IntoAnswer {
+    fn into_answer(self) -> Result<Answer, String>;
+}
+
+impl<T: Into<Answer>> IntoAnswer for T {
+    fn into_answer(self) -> Result<Answer, String> {
+        Ok(self.into())
+    }
+}
+
+impl<T: Into<Answer>, E: Display> IntoAnswer for Result<T, E> {
+    fn into_answer(self) -> Result<Answer, String> {
+        self.map(Into::into).map_err(|e| e.to_string())
+    }
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Number(n) => write!(f, "{}", n),
+            Answer::Text(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum SolveError {
+    UnknownDay(u8),
+    UnknownPart(u8),
+    Unimplemented { day: u8, part: u8 },
+    Parse(ParseError),
+    // the input parses but the part has no answer for it
+    Failed { day: u8, part: u8, reason: String },
+}
+
+impl Display for SolveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SolveError::UnknownDay(day) => write!(f, "there is no day {}, days go from 1 to 25", day),
+            SolveError::UnknownPart(part) => write!(f, "there is no part {}, parts are 1 or 2", part),
+            SolveError::Unimplemented { day, part } => write!(f, "day {} part {} is not implemented", day, part),
+            SolveError::Parse(e) => e.fmt(f),
+            SolveError::Failed { day, part, reason } => write!(f, "day {} part {}: {}", day, part, reason),
+        }
+    }
+}
+
+impl Error for SolveError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SolveError::Parse(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<ParseError> for SolveError {
+    fn from(e: ParseError) -> Self {
+        SolveError::Parse(e)
+    }
+}
+
+fn solve_puzzle<P: Puzzle>(part: u8, input: &str) -> Result<Answer, SolveError> {
+    if !P::solved(part) {
+        return Err(SolveError::Unimplemented { day: P::DAY, part });
+    }
+
+    let input = P::parse(input)?;
+
+    match part {
+        1 => P::part1(&input).into_answer(),
+        _ => P::part2(&input).into_answer(),
+    }
+    .map_err(|reason| SolveError::Failed { day: P::DAY, part, reason })
+}
+
+pub fn solve(day: u8, part: u8, input: &str) -> Result<Answer, SolveError> {
+    use crate::*;
+
+    if !(1..=2).contains(&part) {
+        return Err(SolveError::UnknownPart(part));
+    }
+
+    let solve_day = match day {
+        1 => solve_puzzle::<day1::Day1>,
+        2 => solve_puzzle::<day2::Day2>,
+        3 => solve_puzzle::<day3::Day3>,
+        4 => solve_puzzle::<day4::Day4>,
+        5 => solve_puzzle::<day5::Day5>,
+        6 => solve_puzzle::<day6::Day6>,
+        7 => solve_puzzle::<day7::Day7>,
+        8 => solve_puzzle::<day8::Day8>,
+        9 => solve_puzzle::<day9::Day9>,
+        10 => solve_puzzle::<day10::Day10>,
+        11 => solve_puzzle::<day11::Day11>,
+        12 => solve_puzzle::<day12::Day12>,
+        13 => solve_puzzle::<day13::Day13>,
+        14 => solve_puzzle::<day14::Day14>,
+        15 => solve_puzzle::<day15::Day15>,
+        16 => solve_puzzle::<day16::Day16>,
+        17 => solve_puzzle::<day17::Day17>,
+        18 => solve_puzzle::<day18::Day18>,
+        19 => solve_puzzle::<day19::Day19>,
+        20 => solve_puzzle::<day20::Day20>,
+        21 => solve_puzzle::<day21::Day21>,
+        22 => solve_puzzle::<day22::Day22>,
+        23 => solve_puzzle::<day23::Day23>,
+        24 => solve_puzzle::<day24::Day24>,
+        25 => solve_puzzle::<day25::Day25>,
+        _ => return Err(SolveError::UnknownDay(day)),
+    };
+
+    solve_day(part, parse::trim_input(input))
+}
+
+#[test]
+fn test_solve() {
+    assert_eq!(solve(1, 1, "199\n200\n208\n210\n200\n207\n240\n269\n260\n263\n"), Ok(Answer::Number(7)));
+    assert_eq!(solve(1, 2, "199\n200\n208\n210\n200\n207\n240\n269\n260\n263"), Ok(Answer::Number(5)));
+    assert_eq!(solve(1, 1, "199\r\n200\r\n208\r\n"), Ok(Answer::Number(2)));
+    assert_eq!(solve(7, 1, "16,1,2,0,4,2,7,1,2,14"), Ok(Answer::Number(37)));
+    assert_eq!(
+        solve(13, 2, "0,0\n4,0\n0,1\n\nfold along x=2").map(|answer| answer.to_string()),
+        Ok("#.\n#.".to_string()),
+    );
+}
+
+#[test]
+fn test_number_answers() {
+    assert_eq!(Answer::from(7_u32), Answer::Number(7));
+    assert_eq!(Answer::from(-7_isize), Answer::Number(-7));
+    assert_eq!(Answer::from(i64::MAX as u64), Answer::Number(i64::MAX));
+    assert_eq!(Answer::from(u64::MAX), Answer::Text("18446744073709551615".into()));
+    assert_eq!(Answer::from(usize::MAX).to_string(), usize::MAX.to_string());
+}
+
+#[test]
+fn test_solve_errors() {
+    assert_eq!(solve(0, 1, ""), Err(SolveError::UnknownDay(0)));
+    assert_eq!(solve(26, 1, ""), Err(SolveError::UnknownDay(26)));
+    assert_eq!(solve(1, 3, ""), Err(SolveError::UnknownPart(3)));
+    assert_eq!(solve(25, 2, ".>\nvv"), Err(SolveError::Unimplemented { day: 25, part: 2 }));
+    assert_eq!(solve(1, 1, "199\nabc"), Err(SolveError::Parse(ParseError::new(1, 2, 1, "a depth measurement"))));
+    assert_eq!(
+        SolveError::Unimplemented { day: 25, part: 2 }.to_string(),
+        "day 25 part 2 is not implemented",
+    );
+}